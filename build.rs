@@ -0,0 +1,43 @@
+// Provides the built-in post processing shaders for include_compiled_shader!.
+// Precompiled shaders/<name>.hlsl.sks files are used as they are, so building
+// the crate doesn't need skshaderc. Only a shader without one gets compiled,
+// with the same helper apps can use for their own shaders.
+#[path = "src/shader_build.rs"]
+#[allow(dead_code)]
+mod shader_build;
+
+use std::path::PathBuf;
+
+const SHADERS: [&str; 3] = ["post_blur", "post_tonemap", "post_vignette"];
+
+fn main() {
+	println!("cargo:rerun-if-changed=build.rs");
+	let manifest_dir = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR").unwrap());
+	let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").unwrap()).join("shaders");
+	std::fs::create_dir_all(&out_dir).unwrap();
+
+	let mut missing = Vec::new();
+	for name in SHADERS {
+		let precompiled = manifest_dir.join(format!("shaders/{name}.hlsl.sks"));
+		println!("cargo:rerun-if-changed={}", precompiled.display());
+		if precompiled.exists() {
+			std::fs::copy(&precompiled, out_dir.join(format!("{name}.hlsl.sks"))).unwrap();
+		} else {
+			missing.push(name);
+		}
+	}
+	if missing.is_empty() {
+		return;
+	}
+
+	let compiled = shader_build::ShaderCompiler::new().compile();
+	for name in missing {
+		if !compiled.contains(&out_dir.join(format!("{name}.hlsl.sks"))) {
+			panic!(
+				"shaders/{name}.hlsl has no precompiled shaders/{name}.hlsl.sks, and couldn't be \
+				 compiled. Install StereoKit's skshaderc, or point the SKSHADERC environment \
+				 variable at it."
+			);
+		}
+	}
+}
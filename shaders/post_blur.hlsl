@@ -0,0 +1,45 @@
+#include "stereokit.hlsli"
+
+// Post processing effect for stereokit::post_process, a 9 tap gaussian blur
+// along `direction`. Run it twice, once horizontal and once vertical, for a
+// full blur.
+
+//--name = stereokit_rs/post_blur
+//--source = white
+//--direction = 1, 0
+//--radius = 1
+float2 direction;
+float  radius;
+
+Texture2D    source   : register(t0);
+SamplerState source_s : register(s0);
+// Filled in by StereoKit: width, height, mip count
+float4       source_i;
+
+struct vsIn {
+	float4 pos : SV_Position;
+	float2 uv  : TEXCOORD0;
+};
+struct psIn {
+	float4 pos : SV_POSITION;
+	float2 uv  : TEXCOORD0;
+};
+
+psIn vs(vsIn input) {
+	psIn o;
+	o.pos = input.pos;
+	o.uv  = input.uv;
+	return o;
+}
+
+static const float weights[5] = { 0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216 };
+
+float4 ps(psIn input) : SV_TARGET {
+	float2 step   = direction * radius / source_i.xy;
+	float4 result = source.Sample(source_s, input.uv) * weights[0];
+	for (int i = 1; i < 5; i++) {
+		result += source.Sample(source_s, input.uv + step * i) * weights[i];
+		result += source.Sample(source_s, input.uv - step * i) * weights[i];
+	}
+	return result;
+}
@@ -0,0 +1,37 @@
+#include "stereokit.hlsli"
+
+// Post processing effect for stereokit::post_process, maps HDR color into
+// the 0-1 range with the ACES filmic curve.
+
+//--name = stereokit_rs/post_tonemap
+//--source = white
+//--exposure = 1
+float exposure;
+
+Texture2D    source   : register(t0);
+SamplerState source_s : register(s0);
+
+struct vsIn {
+	float4 pos : SV_Position;
+	float2 uv  : TEXCOORD0;
+};
+struct psIn {
+	float4 pos : SV_POSITION;
+	float2 uv  : TEXCOORD0;
+};
+
+psIn vs(vsIn input) {
+	psIn o;
+	o.pos = input.pos;
+	o.uv  = input.uv;
+	return o;
+}
+
+float3 aces(float3 x) {
+	return saturate((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14));
+}
+
+float4 ps(psIn input) : SV_TARGET {
+	float4 color = source.Sample(source_s, input.uv);
+	return float4(aces(color.rgb * exposure), color.a);
+}
@@ -0,0 +1,37 @@
+#include "stereokit.hlsli"
+
+// Post processing effect for stereokit::post_process, darkens the edges of
+// the image.
+
+//--name = stereokit_rs/post_vignette
+//--source = white
+//--strength = 0.5
+//--radius = 0.75
+float strength;
+float radius;
+
+Texture2D    source   : register(t0);
+SamplerState source_s : register(s0);
+
+struct vsIn {
+	float4 pos : SV_Position;
+	float2 uv  : TEXCOORD0;
+};
+struct psIn {
+	float4 pos : SV_POSITION;
+	float2 uv  : TEXCOORD0;
+};
+
+psIn vs(vsIn input) {
+	psIn o;
+	o.pos = input.pos;
+	o.uv  = input.uv;
+	return o;
+}
+
+float4 ps(psIn input) : SV_TARGET {
+	float4 color    = source.Sample(source_s, input.uv);
+	float  dist     = length(input.uv - 0.5) * 1.41421356;
+	float  vignette = 1 - smoothstep(radius, 1, dist) * strength;
+	return float4(color.rgb * vignette, color.a);
+}
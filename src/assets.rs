@@ -0,0 +1,275 @@
+use crate::{
+	AssetState, MaterialParameter, Model, Shader, Sk, SkResult, Sprite, SpriteType, StereoKitError,
+	StereoKitMultiThread, Tex, TextureAddress,
+};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+pub(crate) struct Slot<T> {
+	result: Option<SkResult<T>>,
+	waker: Option<Waker>,
+}
+
+pub(crate) fn resolve<T>(slot: &Mutex<Slot<T>>, result: SkResult<T>) {
+	let mut slot = slot.lock().unwrap();
+	slot.result = Some(result);
+	if let Some(waker) = slot.waker.take() {
+		waker.wake();
+	}
+}
+
+/// An asset that is still loading in the background. This resolves once
+/// StereoKit has finished loading it, but only while the [`AssetLoader`] that
+/// created it is being stepped!
+pub struct AssetFuture<T> {
+	pub(crate) slot: Arc<Mutex<Slot<T>>>,
+}
+impl<T> AssetFuture<T> {
+	pub(crate) fn new() -> Self {
+		Self {
+			slot: Arc::new(Mutex::new(Slot {
+				result: None,
+				waker: None,
+			})),
+		}
+	}
+	fn ready(result: SkResult<T>) -> Self {
+		let future = Self::new();
+		future.slot.lock().unwrap().result = Some(result);
+		future
+	}
+	/// Takes the result without waiting, if it's ready. This is handy for
+	/// checking on a load from inside the update loop without an executor.
+	pub fn try_take(&mut self) -> Option<SkResult<T>> {
+		self.slot.lock().unwrap().result.take()
+	}
+}
+impl<T> Future for AssetFuture<T> {
+	type Output = SkResult<T>;
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let mut slot = self.slot.lock().unwrap();
+		match slot.result.take() {
+			Some(result) => Poll::Ready(result),
+			None => {
+				slot.waker = Some(cx.waker().clone());
+				Poll::Pending
+			}
+		}
+	}
+}
+
+enum Pending {
+	/// Textures report their own state, so we can tell exactly when they're done.
+	Tex {
+		tex: NonNull<crate::sys::_tex_t>,
+		path: PathBuf,
+		finish: Box<dyn FnOnce(&Sk, SkResult<Tex>)>,
+	},
+	/// Everything else is finished once the asset system has worked through
+	/// all the tasks that were queued up when it was created.
+	Tasks {
+		until: i32,
+		finish: Box<dyn FnOnce(&Sk)>,
+	},
+}
+
+/// Models don't have a state of their own, but their textures do, so this
+/// is the first failed state of any of them.
+fn model_textures_state(sk: &impl StereoKitMultiThread, model: &Model) -> AssetState {
+	for subset in 0..sk.model_subset_count(model) {
+		let Some(material) = sk.model_get_material(model, subset) else {
+			continue;
+		};
+		for index in 0..sk.material_get_param_count(&material) {
+			let Some((name, MaterialParameter::Texture)) =
+				sk.material_get_param_info(&material, index)
+			else {
+				continue;
+			};
+			let Some(tex) = sk.material_try_get_texture(&material, &name) else {
+				continue;
+			};
+			let state = sk.tex_asset_state(&tex);
+			if matches!(
+				state,
+				AssetState::Error | AssetState::NotFound | AssetState::Unsupported
+			) {
+				return state;
+			}
+		}
+	}
+	AssetState::Loaded
+}
+
+/// How far along StereoKit's asset loading system is, from
+/// `assets_current_task` and `assets_total_tasks`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LoadProgress {
+	/// The index of the task currently being worked on.
+	pub current: i32,
+	/// The number of tasks that have ever been queued, including finished ones.
+	pub total: i32,
+}
+impl LoadProgress {
+	/// Progress from 0 to 1, this is 1 when nothing is loading.
+	pub fn fraction(&self) -> f32 {
+		if self.total <= 0 {
+			1.0
+		} else {
+			(self.current as f32 / self.total as f32).clamp(0.0, 1.0)
+		}
+	}
+	pub fn is_done(&self) -> bool {
+		self.current >= self.total
+	}
+}
+
+/// Creates assets with async-friendly loading! StereoKit returns assets right
+/// away and keeps loading them in the background, so the `load_*_async`
+/// functions here hand back an [`AssetFuture`] that resolves once the asset is
+/// actually ready to use, or errors if it failed to load.
+///
+/// Call [`AssetLoader::step`] once per frame from your run loop to resolve
+/// any futures that have finished.
+#[derive(Default)]
+pub struct AssetLoader {
+	pending: Vec<Pending>,
+}
+impl AssetLoader {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Starts loading an image file into a texture, see `tex_create_file`.
+	pub fn load_tex_async(
+		&mut self,
+		sk: &impl StereoKitMultiThread,
+		file: impl AsRef<Path>,
+		srgb_data: bool,
+		priority: i32,
+	) -> AssetFuture<Tex> {
+		let path = file.as_ref().to_path_buf();
+		let tex = match sk.tex_create_file(&path, srgb_data, priority) {
+			Ok(tex) => tex,
+			Err(e) => return AssetFuture::ready(Err(e)),
+		};
+		let future = AssetFuture::new();
+		let slot = future.slot.clone();
+		self.pending.push(Pending::Tex {
+			tex: tex.0,
+			path,
+			finish: Box::new(move |_, result| resolve(&slot, result)),
+		});
+		future
+	}
+
+	/// Starts loading a model file, see `model_create_file`. This resolves once
+	/// everything the model kicked off, like its textures, has finished
+	/// loading, and errors if any of its textures failed to.
+	pub fn load_model_async(
+		&mut self,
+		sk: &impl StereoKitMultiThread,
+		file: impl AsRef<Path>,
+		shader: Option<impl AsRef<Shader>>,
+	) -> AssetFuture<Model> {
+		let path = file.as_ref().to_path_buf();
+		let model = match sk.model_create_file(&path, shader) {
+			Ok(model) => model,
+			Err(e) => return AssetFuture::ready(Err(e)),
+		};
+		let future = AssetFuture::new();
+		let slot = future.slot.clone();
+		self.pending.push(Pending::Tasks {
+			until: sk.assets_total_tasks(),
+			finish: Box::new(move |sk| {
+				let result = match model_textures_state(sk, &model) {
+					AssetState::Loaded => Ok(model),
+					state => Err(StereoKitError::AssetLoad(path, state)),
+				};
+				resolve(&slot, result)
+			}),
+		});
+		future
+	}
+
+	/// Starts loading an image file as a sprite, the same way
+	/// `sprite_create_file` does. The sprite is made once its texture has
+	/// loaded, so this errors if the texture failed to.
+	pub fn load_sprite_async(
+		&mut self,
+		sk: &impl StereoKitMultiThread,
+		file: impl AsRef<Path>,
+		type_: SpriteType,
+		atlas_id: impl AsRef<str>,
+	) -> AssetFuture<Sprite> {
+		let path = file.as_ref().to_path_buf();
+		let tex = match sk.tex_create_file(&path, true, 10) {
+			Ok(tex) => tex,
+			Err(e) => return AssetFuture::ready(Err(e)),
+		};
+		sk.tex_set_address(&tex, TextureAddress::Clamp);
+		let atlas_id = atlas_id.as_ref().to_string();
+		let future = AssetFuture::new();
+		let slot = future.slot.clone();
+		self.pending.push(Pending::Tex {
+			tex: tex.0,
+			path,
+			finish: Box::new(move |sk, result| {
+				let sprite = result.and_then(|tex| {
+					let sprite = sk.sprite_create(&tex, type_, &atlas_id);
+					// The sprite holds its own reference, like sprite_create_file does
+					unsafe { sk.tex_release(tex) };
+					sprite
+				});
+				resolve(&slot, sprite)
+			}),
+		});
+		future
+	}
+
+	/// Checks on every asset that's still loading, and resolves the futures of
+	/// any that are done. Call this once per frame!
+	pub fn step(&mut self, sk: &impl StereoKitMultiThread) {
+		let sk = &Sk::from_handle(sk);
+		let current_task = sk.assets_current_task();
+		let pending = std::mem::take(&mut self.pending);
+		for item in pending {
+			match item {
+				Pending::Tex { tex, path, finish } => match sk.tex_asset_state(Tex(tex)) {
+					AssetState::Loaded => finish(sk, Ok(Tex(tex))),
+					state
+					@ (AssetState::Error | AssetState::NotFound | AssetState::Unsupported) => {
+						finish(sk, Err(StereoKitError::AssetLoad(path, state)))
+					}
+					_ => self.pending.push(Pending::Tex { tex, path, finish }),
+				},
+				Pending::Tasks { until, finish } => {
+					if current_task >= until {
+						finish(sk);
+					} else {
+						self.pending.push(Pending::Tasks { until, finish });
+					}
+				}
+			}
+		}
+	}
+
+	/// How many assets this loader is still waiting on.
+	pub fn pending_count(&self) -> usize {
+		self.pending.len()
+	}
+
+	/// How far along StereoKit is with all of its queued asset loading tasks,
+	/// including ones that weren't started from this loader. Great for loading bars!
+	pub fn progress(&self, sk: &impl StereoKitMultiThread) -> LoadProgress {
+		LoadProgress {
+			current: sk.assets_current_task(),
+			total: sk.assets_total_tasks(),
+		}
+	}
+}
@@ -0,0 +1,200 @@
+use crate::named_colors::WHITE;
+use crate::{
+	ButtonState, Controller, Hand, Handed, Model, Pose, RenderLayer, Settings, Sk, SkDraw,
+	SkSingle, Sound, SoundInstance, StereoKitDraw, StereoKitMultiThread,
+};
+use bevy_app::{App, AppExit, CoreSet, Plugin};
+use bevy_ecs::prelude::*;
+use bevy_transform::prelude::GlobalTransform;
+use bevy_transform::TransformSystem;
+
+/// Runs StereoKit from a Bevy [`App`]! This initializes StereoKit from the
+/// given [`Settings`] when the plugin is added, and replaces the app's runner
+/// with StereoKit's frame loop, so each StereoKit frame runs one app update.
+///
+/// `Sk` is available as a regular resource, and `SkDraw` as a `NonSend`
+/// resource for systems in [`StereoKitSet::Draw`]. Entities with a [`Model`]
+/// and a [`GlobalTransform`] are drawn automatically (filtered by an optional
+/// [`RenderLayer`]), [`Sound`] components are played from their entity's
+/// position, and hand, controller and head input is mirrored into the
+/// [`HandInput`], [`ControllerInput`] and [`HeadInput`] resources.
+#[derive(Default)]
+pub struct StereoKitPlugin {
+	pub settings: Settings,
+}
+
+/// Where StereoKit's systems live in the schedule.
+#[derive(SystemSet, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StereoKitSet {
+	/// Copies this frame's input into resources, runs in `CoreSet::PreUpdate`.
+	Input,
+	/// Systems that draw, or otherwise need `NonSend<SkDraw>`. This runs in
+	/// `CoreSet::PostUpdate` after transforms have been propagated.
+	Draw,
+}
+
+/// The state of both hands this frame.
+#[derive(Resource, Debug, Copy, Clone)]
+pub struct HandInput {
+	pub left: Hand,
+	pub right: Hand,
+}
+impl HandInput {
+	pub fn get(&self, hand: Handed) -> &Hand {
+		match hand {
+			Handed::Right => &self.right,
+			_ => &self.left,
+		}
+	}
+}
+
+/// The state of both controllers and the menu button this frame.
+#[derive(Resource, Debug, Copy, Clone)]
+pub struct ControllerInput {
+	pub left: Controller,
+	pub right: Controller,
+	pub menu: ButtonState,
+}
+impl ControllerInput {
+	pub fn get(&self, hand: Handed) -> &Controller {
+		match hand {
+			Handed::Right => &self.right,
+			_ => &self.left,
+		}
+	}
+}
+
+/// The head and eye poses this frame.
+#[derive(Resource, Debug, Copy, Clone)]
+pub struct HeadInput {
+	pub head: Pose,
+	pub eyes: Pose,
+}
+
+/// Added to entities once their [`Sound`] has started playing. The sound
+/// follows the entity around while it plays, remove this to play it again.
+#[derive(Component, Debug, Copy, Clone)]
+pub struct PlayingSound(pub SoundInstance);
+
+/// How loud a [`Sound`] component should play, defaults to 1.
+#[derive(Component, Debug, Copy, Clone)]
+pub struct SoundVolume(pub f32);
+
+impl Plugin for StereoKitPlugin {
+	fn build(&self, app: &mut App) {
+		let sk = self
+			.settings
+			.clone()
+			.init()
+			.expect("StereoKit failed to initialize");
+
+		app.insert_resource(sk.multithreaded())
+			.insert_non_send_resource(unsafe { SkDraw::create_unsafe() });
+		mirror_input(&sk, app);
+		app.insert_non_send_resource(sk);
+
+		app.configure_set(StereoKitSet::Input.in_base_set(CoreSet::PreUpdate))
+			.configure_set(
+				StereoKitSet::Draw
+					.in_base_set(CoreSet::PostUpdate)
+					.after(TransformSystem::TransformPropagate),
+			)
+			.add_system(input_system.in_set(StereoKitSet::Input))
+			.add_system(draw_models_system.in_set(StereoKitSet::Draw))
+			.add_system(play_sounds_system.in_set(StereoKitSet::Draw))
+			.add_system(move_sounds_system.in_set(StereoKitSet::Draw))
+			.set_runner(stereokit_runner);
+	}
+}
+
+fn stereokit_runner(mut app: App) {
+	let sk = app
+		.world
+		.remove_non_send_resource::<SkSingle>()
+		.expect("StereoKitPlugin's SkSingle was removed before the app started");
+	sk.run(
+		|sk| {
+			app.update();
+			let exit = app
+				.world
+				.get_resource::<Events<AppExit>>()
+				.map(|events| !events.is_empty())
+				.unwrap_or(false);
+			if exit {
+				sk.quit();
+			}
+		},
+		|_| {},
+	);
+}
+
+fn mirror_input(sk: &impl StereoKitMultiThread, app: &mut App) {
+	app.insert_resource(HandInput {
+		left: sk.input_hand(Handed::Left),
+		right: sk.input_hand(Handed::Right),
+	})
+	.insert_resource(ControllerInput {
+		left: sk.input_controller(Handed::Left),
+		right: sk.input_controller(Handed::Right),
+		menu: sk.input_controller_menu(),
+	})
+	.insert_resource(HeadInput {
+		head: sk.input_head(),
+		eyes: sk.input_eyes(),
+	});
+}
+
+fn input_system(
+	sk: Res<Sk>,
+	mut hands: ResMut<HandInput>,
+	mut controllers: ResMut<ControllerInput>,
+	mut head: ResMut<HeadInput>,
+) {
+	hands.left = sk.input_hand(Handed::Left);
+	hands.right = sk.input_hand(Handed::Right);
+	controllers.left = sk.input_controller(Handed::Left);
+	controllers.right = sk.input_controller(Handed::Right);
+	controllers.menu = sk.input_controller_menu();
+	head.head = sk.input_head();
+	head.eyes = sk.input_eyes();
+}
+
+fn draw_models_system(
+	sk: NonSend<SkDraw>,
+	models: Query<(&Model, &GlobalTransform, Option<&RenderLayer>)>,
+) {
+	for (model, transform, layer) in &models {
+		sk.model_draw(
+			model,
+			transform.compute_matrix(),
+			WHITE,
+			layer.copied().unwrap_or_default(),
+		);
+	}
+}
+
+fn play_sounds_system(
+	mut commands: Commands,
+	sk: Res<Sk>,
+	sounds: Query<(Entity, &Sound, &GlobalTransform, Option<&SoundVolume>), Without<PlayingSound>>,
+) {
+	for (entity, sound, transform, volume) in &sounds {
+		let instance = sk.sound_play(
+			sound,
+			transform.translation(),
+			volume.map(|volume| volume.0).unwrap_or(1.0),
+		);
+		commands.entity(entity).insert(PlayingSound(instance));
+	}
+}
+
+fn move_sounds_system(
+	sk: Res<Sk>,
+	sounds: Query<(&PlayingSound, &GlobalTransform), Changed<GlobalTransform>>,
+) {
+	for (playing, transform) in &sounds {
+		if sk.sound_inst_is_playing(playing.0) {
+			sk.sound_inst_set_pos(playing.0, transform.translation());
+		}
+	}
+}
@@ -0,0 +1,254 @@
+use crate::{
+	FovInfo, Pose, Ray, Rect, RenderClear, RenderLayer, StereoKitDraw, StereoKitMultiThread, Tex,
+};
+use glam::{Mat4, Quat, Vec2, Vec3, Vec4};
+
+/// How a [`Camera`] flattens the scene onto the screen.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CameraProjection {
+	/// Regular perspective, with a vertical field of view in degrees. The
+	/// horizontal field of view comes from the aspect ratio of the target.
+	Perspective { fov_degrees: f32 },
+	/// Orthographic, with the height of the view in meters. The width comes
+	/// from the aspect ratio of the target.
+	Orthographic { height_meters: f32 },
+	/// Perspective with a separate angle for each edge of the view, in
+	/// degrees, like the ones MR headsets provide. Left and bottom are usually
+	/// negative.
+	Fov {
+		left: f32,
+		right: f32,
+		top: f32,
+		bottom: f32,
+	},
+}
+
+/// A viewpoint for rendering into a texture with [`Camera::render_to`], or
+/// taking screenshots! This builds the camera and projection matrices the way
+/// StereoKit expects them, so you can think in poses and angles instead.
+#[derive(Debug, Copy, Clone)]
+pub struct Camera {
+	/// Where the camera is, and which way it's looking, Forward is -Z.
+	pub pose: Pose,
+	pub projection: CameraProjection,
+	/// The near clipping plane in meters, this must be greater than zero for perspective.
+	pub near: f32,
+	/// The far clipping plane in meters.
+	pub far: f32,
+	/// The region of the target to draw to, in normalized 0-1 coordinates. A
+	/// width of zero draws to the whole target.
+	pub viewport: Rect,
+}
+
+impl Default for Camera {
+	fn default() -> Self {
+		Self::perspective(Pose::IDENTITY, 90.0)
+	}
+}
+
+impl Camera {
+	/// A perspective camera with a vertical field of view in degrees, using StereoKit's default clip planes.
+	pub fn perspective(pose: impl Into<Pose>, fov_degrees: f32) -> Self {
+		Self {
+			pose: pose.into(),
+			projection: CameraProjection::Perspective { fov_degrees },
+			near: 0.08,
+			far: 50.0,
+			viewport: Rect {
+				x: 0.0,
+				y: 0.0,
+				w: 0.0,
+				h: 0.0,
+			},
+		}
+	}
+
+	/// An orthographic camera that shows this many meters vertically.
+	pub fn orthographic(pose: impl Into<Pose>, height_meters: f32) -> Self {
+		Self {
+			projection: CameraProjection::Orthographic { height_meters },
+			near: 0.0,
+			..Self::perspective(pose, 90.0)
+		}
+	}
+
+	/// A perspective camera with the per-edge angles of a [`FovInfo`].
+	pub fn from_fov(pose: impl Into<Pose>, fov: FovInfo) -> Self {
+		Self {
+			projection: CameraProjection::Fov {
+				left: fov.left,
+				right: fov.right,
+				top: fov.top,
+				bottom: fov.bottom,
+			},
+			..Self::perspective(pose, 90.0)
+		}
+	}
+
+	/// A camera that sees what the user sees, from their head pose and the
+	/// display's field of view. If the display doesn't report a field of
+	/// view, like in flatscreen mode, this falls back to 90 degrees.
+	pub fn from_head(sk: &impl StereoKitMultiThread) -> Self {
+		let head = sk.input_head();
+		let fov = sk.device_display_get_fov();
+		if fov.right - fov.left > 0.0 && fov.top - fov.bottom > 0.0 {
+			Self::from_fov(head, fov)
+		} else {
+			Self::perspective(head, 90.0)
+		}
+	}
+
+	/// A perspective camera at `from`, looking at `at`, same as `render_screenshot` takes.
+	pub fn look_at(from: impl Into<Vec3>, at: impl Into<Vec3>, fov_degrees: f32) -> Self {
+		let from = from.into();
+		let view = Mat4::look_at_rh(from, at.into(), Vec3::Y);
+		let orientation = Quat::from_mat4(&view.inverse());
+		Self::perspective(Pose::new(from, orientation), fov_degrees)
+	}
+
+	pub fn with_clip(mut self, near: f32, far: f32) -> Self {
+		self.near = near;
+		self.far = far;
+		self
+	}
+
+	pub fn with_viewport(mut self, viewport: Rect) -> Self {
+		self.viewport = viewport;
+		self
+	}
+
+	/// The camera's transform in world space, this is what `render_to` takes as its `camera`.
+	pub fn camera_matrix(&self) -> Mat4 {
+		Mat4::from_rotation_translation(self.pose.orientation, self.pose.position)
+	}
+
+	/// Transforms from world space into the camera's space, the inverse of [`Camera::camera_matrix`].
+	pub fn view_matrix(&self) -> Mat4 {
+		self.camera_matrix().inverse()
+	}
+
+	/// The projection matrix for a target with this width/height aspect ratio.
+	/// This is right handed with a 0-1 depth range, which is what StereoKit
+	/// uses for `render_to`.
+	pub fn projection_matrix(&self, aspect: f32) -> Mat4 {
+		match self.projection {
+			CameraProjection::Perspective { fov_degrees } => {
+				Mat4::perspective_rh(fov_degrees.to_radians(), aspect, self.near, self.far)
+			}
+			CameraProjection::Orthographic { height_meters } => {
+				let half_height = height_meters * 0.5;
+				let half_width = half_height * aspect;
+				Mat4::orthographic_rh(
+					-half_width,
+					half_width,
+					-half_height,
+					half_height,
+					self.near,
+					self.far,
+				)
+			}
+			CameraProjection::Fov {
+				left,
+				right,
+				top,
+				bottom,
+			} => {
+				let (n, f) = (self.near, self.far);
+				let l = n * left.to_radians().tan();
+				let r = n * right.to_radians().tan();
+				let t = n * top.to_radians().tan();
+				let b = n * bottom.to_radians().tan();
+				Mat4::from_cols(
+					Vec4::new(2.0 * n / (r - l), 0.0, 0.0, 0.0),
+					Vec4::new(0.0, 2.0 * n / (t - b), 0.0, 0.0),
+					Vec4::new((r + l) / (r - l), (t + b) / (t - b), f / (n - f), -1.0),
+					Vec4::new(0.0, 0.0, n * f / (n - f), 0.0),
+				)
+			}
+		}
+	}
+
+	/// The part of a target of this size, in pixels, that the viewport covers.
+	pub fn viewport_pixels(&self, target_size: impl Into<Vec2>) -> Rect {
+		let size = target_size.into();
+		if self.viewport.w <= 0.0 || self.viewport.h <= 0.0 {
+			return Rect {
+				x: 0.0,
+				y: 0.0,
+				w: size.x,
+				h: size.y,
+			};
+		}
+		Rect {
+			x: self.viewport.x * size.x,
+			y: self.viewport.y * size.y,
+			w: self.viewport.w * size.x,
+			h: self.viewport.h * size.y,
+		}
+	}
+
+	/// The ray through a pixel on a target of this size, measured from the
+	/// top left corner, like `ray_from_mouse` but for any camera.
+	pub fn ray_from_pixel(&self, pixel: impl Into<Vec2>, target_size: impl Into<Vec2>) -> Ray {
+		let pixel = pixel.into();
+		let viewport = self.viewport_pixels(target_size);
+		let ndc = Vec2::new(
+			(pixel.x - viewport.x) / viewport.w * 2.0 - 1.0,
+			1.0 - (pixel.y - viewport.y) / viewport.h * 2.0,
+		);
+		let inverse =
+			(self.projection_matrix(viewport.w / viewport.h) * self.view_matrix()).inverse();
+		let near = inverse.project_point3(ndc.extend(0.0));
+		let far = inverse.project_point3(ndc.extend(1.0));
+		Ray::new(near, (far - near).normalize())
+	}
+
+	/// Renders the scene from this camera into a rendertarget texture, see `render_to`.
+	pub fn render_to(
+		&self,
+		sk: &impl StereoKitDraw,
+		to_rendertarget: impl AsRef<Tex>,
+		layer_filter: RenderLayer,
+		clear: RenderClear,
+	) {
+		let target = to_rendertarget.as_ref();
+		let viewport = self.viewport_pixels(Vec2::new(
+			sk.tex_get_width(target) as f32,
+			sk.tex_get_height(target) as f32,
+		));
+		sk.render_to(
+			target,
+			self.camera_matrix(),
+			self.projection_matrix(viewport.w / viewport.h),
+			layer_filter,
+			clear,
+			self.viewport,
+		)
+	}
+
+	/// Schedules a .jpg screenshot from this camera, see `render_screenshot`.
+	/// Screenshots are always perspective, so orthographic cameras use a 90
+	/// degree field of view, and [`CameraProjection::Fov`] uses its vertical
+	/// extent.
+	pub fn screenshot(
+		&self,
+		sk: &impl StereoKitDraw,
+		file: impl AsRef<str>,
+		width: i32,
+		height: i32,
+	) {
+		let fov_degrees = match self.projection {
+			CameraProjection::Perspective { fov_degrees } => fov_degrees,
+			CameraProjection::Orthographic { .. } => 90.0,
+			CameraProjection::Fov { top, bottom, .. } => top - bottom,
+		};
+		sk.render_screenshot(
+			file,
+			self.pose.position,
+			self.pose.position + self.pose.forward(),
+			width,
+			height,
+			fov_degrees,
+		)
+	}
+}
@@ -0,0 +1,248 @@
+use crate::assets::{resolve, AssetFuture, Slot};
+use crate::camera::Camera;
+use crate::named_colors::BLACK;
+use crate::{
+	Color32, RenderClear, RenderLayer, StereoKitDraw, StereoKitError, StereoKitMultiThread, Tex,
+	TextureFormat, TextureType,
+};
+use std::sync::{Arc, Mutex};
+
+/// Pixels captured from a render, in 8 bit sRGB RGBA, top row first.
+#[derive(Debug, Clone)]
+pub struct CapturedImage {
+	pub width: u32,
+	pub height: u32,
+	/// Four bytes per pixel, `width * height * 4` long.
+	pub data: Vec<u8>,
+}
+
+impl CapturedImage {
+	/// The pixels as colors, rather than bytes.
+	pub fn pixels(&self) -> &[Color32] {
+		// Color32 is four u8s in RGBA order, same as the data
+		unsafe {
+			std::slice::from_raw_parts(self.data.as_ptr() as *const Color32, self.data.len() / 4)
+		}
+	}
+
+	/// The color of a single pixel, measured from the top left.
+	pub fn pixel(&self, x: u32, y: u32) -> Option<Color32> {
+		if x >= self.width || y >= self.height {
+			return None;
+		}
+		Some(self.pixels()[(y * self.width + x) as usize])
+	}
+
+	/// Encodes the image as a .png file in memory.
+	#[cfg(feature = "png")]
+	pub fn to_png(&self) -> crate::SkResult<Vec<u8>> {
+		let mut png = Vec::new();
+		let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+		encoder.set_color(png::ColorType::Rgba);
+		encoder.set_depth(png::BitDepth::Eight);
+		encoder
+			.write_header()
+			.and_then(|mut writer| writer.write_image_data(&self.data))
+			.map_err(|e| StereoKitError::Encode(e.to_string()))?;
+		Ok(png)
+	}
+
+	/// Encodes the image as a .jpg file in memory, quality is 1-100.
+	#[cfg(feature = "jpeg")]
+	pub fn to_jpeg(&self, quality: u8) -> crate::SkResult<Vec<u8>> {
+		let (Ok(width), Ok(height)) = (u16::try_from(self.width), u16::try_from(self.height))
+		else {
+			return Err(StereoKitError::Encode(format!(
+				"{}x{} is too big for a jpeg, which tops out at 65535 pixels a side",
+				self.width, self.height
+			)));
+		};
+		let mut jpeg = Vec::new();
+		jpeg_encoder::Encoder::new(&mut jpeg, quality)
+			.encode(&self.data, width, height, jpeg_encoder::ColorType::Rgba)
+			.map_err(|e| StereoKitError::Encode(e.to_string()))?;
+		Ok(jpeg)
+	}
+
+	/// Encodes the image as a .qoi file in memory.
+	#[cfg(feature = "qoi")]
+	pub fn to_qoi(&self) -> crate::SkResult<Vec<u8>> {
+		qoi::encode_to_vec(&self.data, self.width, self.height)
+			.map_err(|e| StereoKitError::Encode(e.to_string()))
+	}
+}
+
+enum Deliver {
+	Callback(Box<dyn FnOnce(CapturedImage)>),
+	Future(Arc<Mutex<Slot<CapturedImage>>>),
+}
+
+struct Request {
+	target: Tex,
+	width: u32,
+	height: u32,
+	frame: u64,
+	deliver: Deliver,
+}
+
+/// Renders the scene from a [`Camera`] into memory, instead of into a .jpg
+/// like `render_screenshot` does! Captures render at the end of the frame
+/// they're requested in, and the pixels get delivered on the next call to
+/// [`Capture::step`], so call that once per frame from your run loop. Render
+/// targets are reused between frames, so streaming captures of the same size
+/// doesn't make a new one every frame.
+pub struct Capture {
+	requests: Vec<Request>,
+	/// Targets that have been read back, waiting to be reused.
+	free_targets: Vec<(u32, u32, Tex)>,
+	frame: u64,
+	/// Which layers show up in captures.
+	pub layer_filter: RenderLayer,
+}
+
+impl Default for Capture {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Capture {
+	pub fn new() -> Self {
+		Self {
+			requests: Vec::new(),
+			free_targets: Vec::new(),
+			frame: 0,
+			layer_filter: RenderLayer::default(),
+		}
+	}
+
+	fn request(
+		&mut self,
+		sk: &impl StereoKitDraw,
+		camera: &Camera,
+		width: u32,
+		height: u32,
+		deliver: Deliver,
+	) {
+		let target = match self
+			.free_targets
+			.iter()
+			.position(|(w, h, _)| (*w, *h) == (width, height))
+		{
+			Some(index) => self.free_targets.swap_remove(index).2,
+			None => {
+				let target = sk.tex_gen_color(
+					BLACK,
+					width as i32,
+					height as i32,
+					TextureType::RENDER_TARGET,
+					TextureFormat::RGBA32,
+				);
+				// The zbuffer belongs to the target, and goes when it's released
+				sk.tex_add_zbuffer(&target, TextureFormat::Depth32);
+				target
+			}
+		};
+		camera.render_to(sk, &target, self.layer_filter, RenderClear::All);
+		self.requests.push(Request {
+			target,
+			width,
+			height,
+			frame: self.frame,
+			deliver,
+		});
+	}
+
+	/// Captures a `width` by `height` image from this camera, and hands it to
+	/// `on_captured` once it's done.
+	pub fn capture(
+		&mut self,
+		sk: &impl StereoKitDraw,
+		camera: &Camera,
+		width: u32,
+		height: u32,
+		on_captured: impl FnOnce(CapturedImage) + 'static,
+	) {
+		self.request(
+			sk,
+			camera,
+			width,
+			height,
+			Deliver::Callback(Box::new(on_captured)),
+		);
+	}
+
+	/// Captures a `width` by `height` image from this camera, resolving once
+	/// it's done, as long as this Capture keeps being stepped.
+	pub fn capture_async(
+		&mut self,
+		sk: &impl StereoKitDraw,
+		camera: &Camera,
+		width: u32,
+		height: u32,
+	) -> AssetFuture<CapturedImage> {
+		let future = AssetFuture::new();
+		self.request(
+			sk,
+			camera,
+			width,
+			height,
+			Deliver::Future(future.slot.clone()),
+		);
+		future
+	}
+
+	/// How many captures are still waiting on a frame to finish.
+	pub fn pending_count(&self) -> usize {
+		self.requests.len()
+	}
+
+	/// Reads back the captures from previous frames, and delivers them. Call
+	/// this at the start of each frame, before requesting any new captures.
+	pub fn step(&mut self, sk: &impl StereoKitMultiThread) {
+		// Targets nobody reused last frame aren't needed anymore
+		for (_, _, target) in self.free_targets.drain(..) {
+			// Only this Capture has a Tex for it
+			unsafe { sk.tex_release(target) };
+		}
+		self.frame += 1;
+		let frame = self.frame;
+		let (ready, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.requests)
+			.into_iter()
+			.partition(|request| request.frame < frame);
+		self.requests = waiting;
+
+		for request in ready {
+			let data = sk.tex_get_data(&request.target);
+			let result = if data.len() == (request.width * request.height * 4) as usize {
+				Ok(CapturedImage {
+					width: request.width,
+					height: request.height,
+					data,
+				})
+			} else {
+				Err(StereoKitError::Capture(
+					"render target data wasn't the expected size".to_string(),
+				))
+			};
+			self.free_targets
+				.push((request.width, request.height, request.target));
+			match (request.deliver, result) {
+				(Deliver::Callback(on_captured), Ok(image)) => on_captured(image),
+				(Deliver::Callback(_), Err(e)) => sk.log_warn(e.to_string()),
+				(Deliver::Future(slot), result) => resolve(&slot, result),
+			}
+		}
+	}
+}
+
+impl Drop for Capture {
+	fn drop(&mut self) {
+		// Captures that never got read back still own their targets
+		let pending = self.requests.iter().map(|request| &request.target);
+		let free = self.free_targets.iter().map(|(_, _, target)| target);
+		for target in pending.chain(free) {
+			unsafe { crate::tex_release(target) };
+		}
+	}
+}
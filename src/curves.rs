@@ -0,0 +1,483 @@
+use crate::{gradient_get32, Color32, Gradient, LinePoint, Pose, StereoKitDraw};
+use glam::{Quat, Vec3};
+
+/// Something with a position for every `t` from 0 to 1, like a spline!
+pub trait Curve {
+	fn position(&self, t: f32) -> Vec3;
+
+	/// The direction the curve is heading at `t`, not normalized. The default
+	/// takes a small step along the curve, override it if there's an exact answer.
+	fn tangent(&self, t: f32) -> Vec3 {
+		let step = 0.0005;
+		let a = (t - step).max(0.0);
+		let b = (t + step).min(1.0);
+		(self.position(b) - self.position(a)) / (b - a)
+	}
+
+	/// A pose on the curve at `t`, with Forward pointing along the curve. This
+	/// is handy for moving things along a path.
+	fn pose(&self, t: f32) -> Pose {
+		let direction = self.tangent(t).normalize_or_zero();
+		let orientation = if direction == Vec3::ZERO {
+			Quat::IDENTITY
+		} else {
+			Quat::from_rotation_arc(Vec3::NEG_Z, direction)
+		};
+		Pose::new(self.position(t), orientation)
+	}
+}
+
+/// A cubic Bezier curve, from `start` to `end`, pulled towards the two control points.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CubicBezier {
+	pub start: Vec3,
+	pub control_a: Vec3,
+	pub control_b: Vec3,
+	pub end: Vec3,
+}
+
+impl CubicBezier {
+	pub fn new(
+		start: impl Into<Vec3>,
+		control_a: impl Into<Vec3>,
+		control_b: impl Into<Vec3>,
+		end: impl Into<Vec3>,
+	) -> Self {
+		Self {
+			start: start.into(),
+			control_a: control_a.into(),
+			control_b: control_b.into(),
+			end: end.into(),
+		}
+	}
+}
+
+impl Curve for CubicBezier {
+	fn position(&self, t: f32) -> Vec3 {
+		let u = 1.0 - t;
+		self.start * (u * u * u)
+			+ self.control_a * (3.0 * u * u * t)
+			+ self.control_b * (3.0 * u * t * t)
+			+ self.end * (t * t * t)
+	}
+
+	fn tangent(&self, t: f32) -> Vec3 {
+		let u = 1.0 - t;
+		(self.control_a - self.start) * (3.0 * u * u)
+			+ (self.control_b - self.control_a) * (6.0 * u * t)
+			+ (self.end - self.control_b) * (3.0 * t * t)
+	}
+}
+
+/// A smooth curve that passes through every one of its points. `t` is spread
+/// evenly across the segments between points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatmullRom {
+	pub points: Vec<Vec3>,
+	/// Connects the last point back around to the first.
+	pub closed: bool,
+}
+
+impl CatmullRom {
+	pub fn new(points: impl Into<Vec<Vec3>>, closed: bool) -> Self {
+		Self {
+			points: points.into(),
+			closed,
+		}
+	}
+
+	fn segment_count(&self) -> usize {
+		match self.points.len() {
+			0 | 1 => 0,
+			count if self.closed => count,
+			count => count - 1,
+		}
+	}
+
+	fn point(&self, index: isize) -> Vec3 {
+		let count = self.points.len() as isize;
+		let index = if self.closed {
+			index.rem_euclid(count)
+		} else {
+			index.clamp(0, count - 1)
+		};
+		self.points[index as usize]
+	}
+
+	/// Which segment `t` lands in, and how far along that segment it is.
+	fn locate(&self, t: f32) -> (isize, f32) {
+		let segments = self.segment_count();
+		let scaled = t.clamp(0.0, 1.0) * segments as f32;
+		let segment = (scaled.floor() as usize).min(segments - 1);
+		(segment as isize, scaled - segment as f32)
+	}
+}
+
+impl Curve for CatmullRom {
+	fn position(&self, t: f32) -> Vec3 {
+		if self.segment_count() == 0 {
+			return self.points.first().copied().unwrap_or_default();
+		}
+		let (i, t) = self.locate(t);
+		let (p0, p1, p2, p3) = (
+			self.point(i - 1),
+			self.point(i),
+			self.point(i + 1),
+			self.point(i + 2),
+		);
+		let t2 = t * t;
+		let t3 = t2 * t;
+		0.5 * ((2.0 * p1)
+			+ (p2 - p0) * t
+			+ (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+			+ (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+	}
+
+	fn tangent(&self, t: f32) -> Vec3 {
+		let segments = self.segment_count();
+		if segments == 0 {
+			return Vec3::ZERO;
+		}
+		let (i, t) = self.locate(t);
+		let (p0, p1, p2, p3) = (
+			self.point(i - 1),
+			self.point(i),
+			self.point(i + 1),
+			self.point(i + 2),
+		);
+		let t2 = t * t;
+		// Scaled by the segment count, since t covers the whole curve
+		0.5 * segments as f32
+			* ((p2 - p0)
+				+ (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * (2.0 * t)
+				+ (3.0 * p1 - p0 - 3.0 * p2 + p3) * (3.0 * t2))
+	}
+}
+
+/// Part of a circle, starting at `center + start_direction * radius` and
+/// sweeping `angle` radians around `normal`, counter-clockwise when looking
+/// down at the normal.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CircleArc {
+	pub center: Vec3,
+	pub normal: Vec3,
+	pub start_direction: Vec3,
+	pub radius: f32,
+	pub angle: f32,
+}
+
+impl CircleArc {
+	pub fn new(
+		center: impl Into<Vec3>,
+		normal: impl Into<Vec3>,
+		start_direction: impl Into<Vec3>,
+		radius: f32,
+		angle: f32,
+	) -> Self {
+		Self {
+			center: center.into(),
+			normal: normal.into().normalize_or_zero(),
+			start_direction: start_direction.into().normalize_or_zero(),
+			radius,
+			angle,
+		}
+	}
+}
+
+impl Curve for CircleArc {
+	fn position(&self, t: f32) -> Vec3 {
+		let rotation = Quat::from_axis_angle(self.normal, self.angle * t);
+		self.center + rotation * self.start_direction * self.radius
+	}
+
+	fn tangent(&self, t: f32) -> Vec3 {
+		let rotation = Quat::from_axis_angle(self.normal, self.angle * t);
+		self.normal.cross(rotation * self.start_direction) * (self.radius * self.angle)
+	}
+}
+
+/// Breaks a curve into points, adding more where it bends so that no straight
+/// piece is further than `tolerance` meters from the real curve. Returns each
+/// point with its `t`.
+pub fn tessellate(curve: &impl Curve, tolerance: f32) -> Vec<(f32, Vec3)> {
+	fn subdivide(
+		curve: &impl Curve,
+		tolerance: f32,
+		(t0, p0): (f32, Vec3),
+		(t1, p1): (f32, Vec3),
+		depth: u32,
+		out: &mut Vec<(f32, Vec3)>,
+	) {
+		let tm = (t0 + t1) * 0.5;
+		let pm = curve.position(tm);
+		// Always split a few times, so we don't miss an S bend whose midpoint
+		// happens to land on the chord
+		let error = pm.distance((p0 + p1) * 0.5);
+		if depth < 12 && (depth < 3 || error > tolerance) {
+			subdivide(curve, tolerance, (t0, p0), (tm, pm), depth + 1, out);
+			subdivide(curve, tolerance, (tm, pm), (t1, p1), depth + 1, out);
+		} else {
+			out.push((t1, p1));
+		}
+	}
+
+	let start = (0.0, curve.position(0.0));
+	let end = (1.0, curve.position(1.0));
+	let mut points = vec![start];
+	subdivide(curve, tolerance.max(0.0001), start, end, 0, &mut points);
+	points
+}
+
+/// A curve that's been measured, so it can be walked at a steady speed!
+/// Plain `t` moves faster over parts of a curve where its points are spread
+/// out, this maps distances along the curve back to `t`.
+pub struct CurveSampler<'a, C: Curve> {
+	pub curve: &'a C,
+	/// (t, distance from the start) for each tessellated point.
+	table: Vec<(f32, f32)>,
+	points: Vec<Vec3>,
+}
+
+impl<'a, C: Curve> CurveSampler<'a, C> {
+	pub fn new(curve: &'a C, tolerance: f32) -> Self {
+		let tessellated = tessellate(curve, tolerance);
+		let mut table = Vec::with_capacity(tessellated.len());
+		let mut distance = 0.0;
+		for (index, (t, point)) in tessellated.iter().enumerate() {
+			if index > 0 {
+				distance += point.distance(tessellated[index - 1].1);
+			}
+			table.push((*t, distance));
+		}
+		Self {
+			curve,
+			table,
+			points: tessellated.into_iter().map(|(_, point)| point).collect(),
+		}
+	}
+
+	/// The length of the curve in meters.
+	pub fn length(&self) -> f32 {
+		self.table
+			.last()
+			.map(|(_, distance)| *distance)
+			.unwrap_or(0.0)
+	}
+
+	/// The `t` that's this many meters along the curve.
+	pub fn t_at_distance(&self, distance: f32) -> f32 {
+		let distance = distance.clamp(0.0, self.length());
+		let index = self
+			.table
+			.partition_point(|(_, d)| *d < distance)
+			.clamp(1, self.table.len().max(2) - 1);
+		let Some(&(t1, d1)) = self.table.get(index) else {
+			return 0.0;
+		};
+		let (t0, d0) = self.table[index - 1];
+		if d1 - d0 <= f32::EPSILON {
+			return t1;
+		}
+		t0 + (t1 - t0) * (distance - d0) / (d1 - d0)
+	}
+
+	pub fn position_at_distance(&self, distance: f32) -> Vec3 {
+		self.curve.position(self.t_at_distance(distance))
+	}
+	pub fn tangent_at_distance(&self, distance: f32) -> Vec3 {
+		self.curve.tangent(self.t_at_distance(distance))
+	}
+	pub fn pose_at_distance(&self, distance: f32) -> Pose {
+		self.curve.pose(self.t_at_distance(distance))
+	}
+
+	/// Cuts the measured curve into runs of line points, following `style`.
+	pub fn line_points(&self, style: &CurveStyle) -> Vec<Vec<LinePoint>> {
+		let length = self.length();
+		let (on, off) = match style.pattern {
+			LinePattern::Solid => (length, 0.0),
+			LinePattern::Dashed { dash, gap } => (dash.max(0.0001), gap.max(0.0)),
+			LinePattern::Dotted { spacing } => {
+				let dot = style.thickness.max(0.0001);
+				(dot, (spacing - dot).max(0.0))
+			}
+		};
+
+		let point_at = |distance: f32, position: Vec3| LinePoint {
+			pt: position,
+			thickness: style.thickness,
+			color: style.color.at(distance / length),
+		};
+
+		let mut runs = Vec::new();
+		if length <= 0.0 {
+			return runs;
+		}
+		let mut run_start = 0.0;
+		while run_start < length {
+			let run_end = (run_start + on).min(length);
+			let mut run = vec![point_at(run_start, self.position_on_polyline(run_start))];
+			// The distances only grow, so the points inside this run are a slice of the table
+			let first = self.table.partition_point(|(_, d)| *d <= run_start);
+			let last = self.table.partition_point(|(_, d)| *d < run_end).max(first);
+			for ((_, distance), point) in self.table[first..last]
+				.iter()
+				.zip(&self.points[first..last])
+			{
+				run.push(point_at(*distance, *point));
+			}
+			run.push(point_at(run_end, self.position_on_polyline(run_end)));
+			runs.push(run);
+			run_start = run_end + off;
+		}
+		runs
+	}
+
+	/// Interpolates along the tessellated points, so dashes line up exactly with the lines drawn.
+	fn position_on_polyline(&self, distance: f32) -> Vec3 {
+		let index = self
+			.table
+			.partition_point(|(_, d)| *d < distance)
+			.min(self.table.len().saturating_sub(1));
+		if index == 0 {
+			return self.points.first().copied().unwrap_or_default();
+		}
+		let (_, d0) = self.table[index - 1];
+		let (_, d1) = self.table[index];
+		let amount = if d1 - d0 > f32::EPSILON {
+			(distance - d0) / (d1 - d0)
+		} else {
+			1.0
+		};
+		self.points[index - 1].lerp(self.points[index], amount)
+	}
+}
+
+/// Where the color of a curve comes from.
+pub enum CurveColor<'a> {
+	Solid(Color32),
+	/// Sampled from 0 at the start of the curve to 1 at the end.
+	Gradient(&'a Gradient),
+}
+
+impl CurveColor<'_> {
+	fn at(&self, fraction: f32) -> Color32 {
+		match self {
+			CurveColor::Solid(color) => *color,
+			CurveColor::Gradient(gradient) => gradient_get32(*gradient, fraction),
+		}
+	}
+}
+
+/// How a curve's line is broken up.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LinePattern {
+	Solid,
+	/// Dashes `dash` meters long, with `gap` meters between them.
+	Dashed {
+		dash: f32,
+		gap: f32,
+	},
+	/// Dots as wide as the line is thick, `spacing` meters apart.
+	Dotted {
+		spacing: f32,
+	},
+}
+
+/// How [`draw_curve`] draws a curve.
+pub struct CurveStyle<'a> {
+	/// Line thickness in meters.
+	pub thickness: f32,
+	pub color: CurveColor<'a>,
+	pub pattern: LinePattern,
+	/// How far the drawn line may stray from the real curve, in meters.
+	pub tolerance: f32,
+}
+
+impl<'a> CurveStyle<'a> {
+	pub fn new(thickness: f32, color: CurveColor<'a>) -> Self {
+		Self {
+			thickness,
+			color,
+			pattern: LinePattern::Solid,
+			tolerance: 0.001,
+		}
+	}
+
+	pub fn with_pattern(mut self, pattern: LinePattern) -> Self {
+		self.pattern = pattern;
+		self
+	}
+
+	pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+		self.tolerance = tolerance;
+		self
+	}
+}
+
+/// Draws a curve for this frame with `line_add_listv`, see [`CurveStyle`].
+pub fn draw_curve(sk: &impl StereoKitDraw, curve: &impl Curve, style: &CurveStyle) {
+	let sampler = CurveSampler::new(curve, style.tolerance);
+	for run in sampler.line_points(style) {
+		sk.line_add_listv(&run);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A straight line from the origin to +X, with `t` moving at a steady speed.
+	fn line() -> CubicBezier {
+		CubicBezier::new(Vec3::ZERO, Vec3::X / 3.0, Vec3::X * (2.0 / 3.0), Vec3::X)
+	}
+
+	#[test]
+	fn tessellation() {
+		// A straight curve only gets the minimum number of splits
+		let points = tessellate(&line(), 0.01);
+		assert_eq!(points.len(), 9);
+		assert_eq!(points.first().unwrap().0, 0.0);
+		assert_eq!(points.last().unwrap().0, 1.0);
+
+		let tolerance = 0.001;
+		let arc = CircleArc::new(Vec3::ZERO, Vec3::Y, Vec3::X, 1.0, std::f32::consts::PI);
+		let points = tessellate(&arc, tolerance);
+		assert!(points.len() > 9);
+		for pair in points.windows(2) {
+			let ((t0, p0), (t1, p1)) = (pair[0], pair[1]);
+			assert!(t1 > t0);
+			assert!((p1.length() - 1.0).abs() < 1e-4);
+			let middle = arc.position((t0 + t1) * 0.5);
+			assert!(middle.distance((p0 + p1) * 0.5) <= tolerance + 1e-5);
+		}
+	}
+
+	#[test]
+	fn patterns() {
+		let line = line();
+		let sampler = CurveSampler::new(&line, 0.001);
+		assert!((sampler.length() - 1.0).abs() < 1e-4);
+
+		let white = CurveColor::Solid(Color32 {
+			r: 255,
+			g: 255,
+			b: 255,
+			a: 255,
+		});
+		let style = CurveStyle::new(0.01, white);
+		assert_eq!(sampler.line_points(&style).len(), 1);
+		let style = style.with_pattern(LinePattern::Dashed {
+			dash: 0.3,
+			gap: 0.3,
+		});
+		let dashes = sampler.line_points(&style);
+		assert_eq!(dashes.len(), 2);
+		assert!((dashes[1][0].pt.x - 0.6).abs() < 1e-4);
+		assert!((dashes[1].last().unwrap().pt.x - 0.9).abs() < 1e-4);
+		// Dots closer together than they are wide still finish
+		let style = style.with_pattern(LinePattern::Dotted { spacing: 0.005 });
+		let dots = sampler.line_points(&style);
+		assert!((99..=101).contains(&dots.len()));
+	}
+}
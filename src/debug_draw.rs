@@ -0,0 +1,418 @@
+use crate::camera::Camera;
+use crate::{Bounds, Color128, Color32, Plane, Pose, Ray, StereoKitDraw, TextAlign, TextStyle};
+use glam::{Mat4, Quat, Vec2, Vec3};
+use std::f32::consts::{PI, TAU};
+
+/// How many segments circles and arcs are made of.
+const CIRCLE_SEGMENTS: usize = 24;
+
+enum Shape {
+	Line(Vec3, Vec3),
+	Box(Mat4, Bounds),
+	Sphere(Vec3, f32),
+	Capsule(Vec3, Vec3, f32),
+	Frustum(Mat4),
+	Arrow(Vec3, Vec3),
+	Grid(Pose, Vec2, u32),
+	Circle(Vec3, Vec3, f32),
+	Axis(Pose, f32),
+	Label(String, Vec3),
+}
+
+/// Queues up a shape on a [`DebugDraw`](crate::debug_draw::DebugDraw), like
+/// `debug_draw!(debug, line(start, end, color, 0.0))`. Without the
+/// `debug-draw` feature this expands to nothing, so the arguments, such as a
+/// `format!` for a label, are never evaluated.
+#[cfg(feature = "debug-draw")]
+#[macro_export]
+macro_rules! debug_draw {
+	($debug:expr, $shape:ident($($arg:expr),* $(,)?)) => {
+		$debug.$shape($($arg),*)
+	};
+}
+
+/// Queues up a shape on a [`DebugDraw`](crate::debug_draw::DebugDraw), like
+/// `debug_draw!(debug, line(start, end, color, 0.0))`. Without the
+/// `debug-draw` feature this expands to nothing, so the arguments, such as a
+/// `format!` for a label, are never evaluated.
+#[cfg(not(feature = "debug-draw"))]
+#[macro_export]
+macro_rules! debug_draw {
+	($debug:expr, $shape:ident($($arg:expr),* $(,)?)) => {
+		()
+	};
+}
+
+struct Entry {
+	shape: Shape,
+	color: Color32,
+	/// Seconds left to show this for, shapes are always drawn at least once.
+	remaining: f32,
+}
+
+/// Debug visualization, built on top of `line_add` and `text_add_at`! Queue
+/// up shapes from anywhere in your app, and they'll show up the next time
+/// [`DebugDraw::draw`] is called. Each shape can stay visible for a number of
+/// seconds, which is handy for one-off events like a collision, otherwise
+/// it's drawn once.
+///
+/// Nothing is drawn unless the `debug-draw` feature is on, so turn it on for
+/// the builds you want to see it in, and set `enabled` to turn it off at
+/// runtime. To have the calls compile down to nothing without the feature,
+/// arguments included, go through the [`debug_draw!`](crate::debug_draw!)
+/// macro instead of calling the methods directly.
+pub struct DebugDraw {
+	entries: Vec<Entry>,
+	pub enabled: bool,
+	/// Line thickness in meters.
+	pub thickness: f32,
+	/// How tall label text is, in meters.
+	pub label_size: f32,
+}
+
+impl Default for DebugDraw {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl DebugDraw {
+	pub fn new() -> Self {
+		Self {
+			entries: Vec::new(),
+			enabled: true,
+			thickness: 0.002,
+			label_size: 0.02,
+		}
+	}
+
+	#[inline]
+	fn add(&mut self, shape: Shape, color: Color32, duration: f32) {
+		if cfg!(feature = "debug-draw") && self.enabled {
+			self.entries.push(Entry {
+				shape,
+				color,
+				remaining: duration,
+			});
+		}
+	}
+
+	/// A single line between two points.
+	pub fn line(
+		&mut self,
+		start: impl Into<Vec3>,
+		end: impl Into<Vec3>,
+		color: Color32,
+		duration: f32,
+	) {
+		self.add(Shape::Line(start.into(), end.into()), color, duration)
+	}
+
+	/// The edges of a Bounds, in world space.
+	pub fn wire_box(&mut self, bounds: Bounds, color: Color32, duration: f32) {
+		self.add(Shape::Box(Mat4::IDENTITY, bounds), color, duration)
+	}
+
+	/// The edges of a Bounds in the space of `transform`, like a Model's
+	/// bounds and its transform.
+	pub fn wire_box_transformed(
+		&mut self,
+		transform: impl Into<Mat4>,
+		bounds: Bounds,
+		color: Color32,
+		duration: f32,
+	) {
+		self.add(Shape::Box(transform.into(), bounds), color, duration)
+	}
+
+	/// A sphere, as three circles around its axes.
+	pub fn sphere(&mut self, center: impl Into<Vec3>, radius: f32, color: Color32, duration: f32) {
+		self.add(Shape::Sphere(center.into(), radius), color, duration)
+	}
+
+	/// A capsule, the shape swept by a sphere from `start` to `end`.
+	pub fn capsule(
+		&mut self,
+		start: impl Into<Vec3>,
+		end: impl Into<Vec3>,
+		radius: f32,
+		color: Color32,
+		duration: f32,
+	) {
+		self.add(
+			Shape::Capsule(start.into(), end.into(), radius),
+			color,
+			duration,
+		)
+	}
+
+	/// The volume a Camera sees, for a target with this aspect ratio.
+	pub fn frustum(&mut self, camera: &Camera, aspect: f32, color: Color32, duration: f32) {
+		let inverse = (camera.projection_matrix(aspect) * camera.view_matrix()).inverse();
+		self.add(Shape::Frustum(inverse), color, duration)
+	}
+
+	/// A line with an arrow head at `end`.
+	pub fn arrow(
+		&mut self,
+		start: impl Into<Vec3>,
+		end: impl Into<Vec3>,
+		color: Color32,
+		duration: f32,
+	) {
+		self.add(Shape::Arrow(start.into(), end.into()), color, duration)
+	}
+
+	/// A ray, drawn as an arrow `length` meters long.
+	pub fn ray(&mut self, ray: Ray, length: f32, color: Color32, duration: f32) {
+		let end = ray.pos + ray.dir.normalize_or_zero() * length;
+		self.add(Shape::Arrow(ray.pos, end), color, duration)
+	}
+
+	/// A grid on the XZ plane of `pose`, `size` meters across with `cells` divisions along each side.
+	pub fn grid(
+		&mut self,
+		pose: impl Into<Pose>,
+		size: impl Into<Vec2>,
+		cells: u32,
+		color: Color32,
+		duration: f32,
+	) {
+		self.add(
+			Shape::Grid(pose.into(), size.into(), cells.max(1)),
+			color,
+			duration,
+		)
+	}
+
+	/// A square patch of a Plane, centered on the point of the plane closest to
+	/// `near`, with an arrow showing its normal.
+	pub fn plane(
+		&mut self,
+		plane: Plane,
+		near: impl Into<Vec3>,
+		size: f32,
+		color: Color32,
+		duration: f32,
+	) {
+		let normal = plane.normal.normalize_or_zero();
+		let near = near.into();
+		let center = near - normal * (near.dot(normal) + plane.d);
+		let orientation = Quat::from_rotation_arc(Vec3::Y, normal);
+		self.add(
+			Shape::Grid(Pose::new(center, orientation), Vec2::splat(size), 4),
+			color,
+			duration,
+		);
+		self.add(
+			Shape::Arrow(center, center + normal * size * 0.5),
+			color,
+			duration,
+		)
+	}
+
+	/// A circle facing along `normal`.
+	pub fn circle(
+		&mut self,
+		center: impl Into<Vec3>,
+		normal: impl Into<Vec3>,
+		radius: f32,
+		color: Color32,
+		duration: f32,
+	) {
+		self.add(
+			Shape::Circle(center.into(), normal.into().normalize_or_zero(), radius),
+			color,
+			duration,
+		)
+	}
+
+	/// An RGB/XYZ axis widget, see `line_add_axis`.
+	pub fn pose(&mut self, pose: impl Into<Pose>, size: f32, duration: f32) {
+		self.add(
+			Shape::Axis(pose.into(), size),
+			Color32 {
+				r: 255,
+				g: 255,
+				b: 255,
+				a: 255,
+			},
+			duration,
+		)
+	}
+
+	/// Text floating at a point in world space, turned to face the user.
+	pub fn label(
+		&mut self,
+		text: impl Into<String>,
+		position: impl Into<Vec3>,
+		color: Color32,
+		duration: f32,
+	) {
+		self.add(Shape::Label(text.into(), position.into()), color, duration)
+	}
+
+	/// Removes everything that's queued up, including shapes with time left.
+	pub fn clear(&mut self) {
+		self.entries.clear();
+	}
+
+	/// Draws everything that's queued up, and forgets shapes once their time
+	/// runs out. Call this once per frame.
+	pub fn draw(&mut self, sk: &impl StereoKitDraw) {
+		if !cfg!(feature = "debug-draw") || !self.enabled {
+			self.entries.clear();
+			return;
+		}
+		let head = sk.input_head().position;
+		for entry in &self.entries {
+			self.draw_shape(sk, &entry.shape, entry.color, head);
+		}
+		let step = sk.time_step_f32();
+		self.entries.retain_mut(|entry| {
+			entry.remaining -= step;
+			entry.remaining > 0.0
+		});
+	}
+
+	fn draw_shape(&self, sk: &impl StereoKitDraw, shape: &Shape, color: Color32, head: Vec3) {
+		let line = |a: Vec3, b: Vec3| sk.line_add(a, b, color, color, self.thickness);
+		let circle = |center: Vec3, normal: Vec3, radius: f32| {
+			let points = circle_points(center, normal, radius);
+			sk.line_add_list(&points, color, self.thickness);
+		};
+		match shape {
+			Shape::Line(a, b) => line(*a, *b),
+			Shape::Box(transform, bounds) => {
+				let half = bounds.dimensions * 0.5;
+				let corner = |x: f32, y: f32, z: f32| {
+					transform.transform_point3(bounds.center + half * Vec3::new(x, y, z))
+				};
+				for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+					line(corner(x, y, -1.0), corner(x, y, 1.0));
+					line(corner(x, -1.0, y), corner(x, 1.0, y));
+					line(corner(-1.0, x, y), corner(1.0, x, y));
+				}
+			}
+			Shape::Sphere(center, radius) => {
+				circle(*center, Vec3::X, *radius);
+				circle(*center, Vec3::Y, *radius);
+				circle(*center, Vec3::Z, *radius);
+			}
+			Shape::Capsule(start, end, radius) => {
+				let axis = (*end - *start).normalize_or_zero();
+				let axis = if axis == Vec3::ZERO { Vec3::Y } else { axis };
+				let (side, other) = axis.any_orthonormal_pair();
+				circle(*start, axis, *radius);
+				circle(*end, axis, *radius);
+				for direction in [side, -side, other, -other] {
+					line(*start + direction * *radius, *end + direction * *radius);
+				}
+				// Half circles over each end cap
+				for (center, cap) in [(*start, -axis), (*end, axis)] {
+					for direction in [side, other] {
+						let points = arc_points(center, direction, cap, *radius);
+						sk.line_add_list(&points, color, self.thickness);
+					}
+				}
+			}
+			Shape::Frustum(inverse) => {
+				let corner = |x: f32, y: f32, z: f32| inverse.project_point3(Vec3::new(x, y, z));
+				for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+					line(corner(x, y, 0.0), corner(x, y, 1.0));
+				}
+				for z in [0.0, 1.0] {
+					let points = [
+						corner(-1.0, -1.0, z),
+						corner(1.0, -1.0, z),
+						corner(1.0, 1.0, z),
+						corner(-1.0, 1.0, z),
+						corner(-1.0, -1.0, z),
+					];
+					sk.line_add_list(&points, color, self.thickness);
+				}
+			}
+			Shape::Arrow(start, end) => {
+				line(*start, *end);
+				let direction = *end - *start;
+				let length = direction.length();
+				if length > 0.0 {
+					let direction = direction / length;
+					let (side, other) = direction.any_orthonormal_pair();
+					let head_size = (length * 0.2).min(0.05);
+					let back = *end - direction * head_size;
+					for offset in [side, -side, other, -other] {
+						line(*end, back + offset * head_size * 0.5);
+					}
+				}
+			}
+			Shape::Grid(pose, size, cells) => {
+				let half = *size * 0.5;
+				let point =
+					|x: f32, z: f32| pose.position + pose.orientation * Vec3::new(x, 0.0, z);
+				for i in 0..=*cells {
+					let t = i as f32 / *cells as f32;
+					let x = -half.x + size.x * t;
+					let z = -half.y + size.y * t;
+					line(point(x, -half.y), point(x, half.y));
+					line(point(-half.x, z), point(half.x, z));
+				}
+			}
+			Shape::Circle(center, normal, radius) => circle(*center, *normal, *radius),
+			Shape::Axis(pose, size) => sk.line_add_axis(*pose, *size),
+			Shape::Label(text, position) => {
+				// Text faces Forward, so point that at the user
+				let to_head = (head - *position).normalize_or_zero();
+				let orientation = if to_head == Vec3::ZERO {
+					Quat::IDENTITY
+				} else {
+					Quat::from_rotation_arc(Vec3::NEG_Z, to_head)
+				};
+				// The default style already has a character height, so scale from that
+				let scale = self.label_size
+					/ sk.text_style_get_char_height(TextStyle::DEFAULT)
+						.max(0.0001);
+				let transform = Mat4::from_scale_rotation_translation(
+					Vec3::splat(scale),
+					orientation,
+					*position,
+				);
+				sk.text_add_at(
+					text,
+					transform,
+					TextStyle::DEFAULT,
+					TextAlign::Center,
+					TextAlign::Center,
+					Vec3::ZERO,
+					Color128::new(
+						color.r as f32 / 255.0,
+						color.g as f32 / 255.0,
+						color.b as f32 / 255.0,
+						color.a as f32 / 255.0,
+					),
+				);
+			}
+		}
+	}
+}
+
+/// Points around a circle facing along `normal`, the last point closes the loop.
+fn circle_points(center: Vec3, normal: Vec3, radius: f32) -> Vec<Vec3> {
+	let (x, y) = normal.any_orthonormal_pair();
+	(0..=CIRCLE_SEGMENTS)
+		.map(|i| {
+			let angle = TAU * i as f32 / CIRCLE_SEGMENTS as f32;
+			center + (x * angle.cos() + y * angle.sin()) * radius
+		})
+		.collect()
+}
+
+/// A half circle from `-side` over `cap` to `side`.
+fn arc_points(center: Vec3, side: Vec3, cap: Vec3, radius: f32) -> Vec<Vec3> {
+	(0..=CIRCLE_SEGMENTS / 2)
+		.map(|i| {
+			let angle = PI * i as f32 / (CIRCLE_SEGMENTS / 2) as f32;
+			center + (-side * angle.cos() + cap * angle.sin()) * radius
+		})
+		.collect()
+}
@@ -0,0 +1,80 @@
+use crate::{Shader, SkResult, StereoKitMultiThread};
+use std::sync::Mutex;
+
+/// A compiled StereoKit shader (.sks) that's baked into the binary, made with
+/// [`include_shader!`](crate::include_shader) or
+/// [`include_compiled_shader!`](crate::include_compiled_shader). The Shader
+/// asset gets created the first time it's asked for, and found by its id
+/// after that.
+pub struct EmbeddedShader {
+	/// The asset id the Shader gets.
+	pub id: &'static str,
+	pub data: &'static [u8],
+	creating: Mutex<()>,
+}
+
+impl EmbeddedShader {
+	pub const fn new(id: &'static str, data: &'static [u8]) -> Self {
+		Self {
+			id,
+			data,
+			creating: Mutex::new(()),
+		}
+	}
+
+	/// Finds the Shader if it's already loaded, or creates it from the embedded data.
+	pub fn shader(&self, sk: &impl StereoKitMultiThread) -> SkResult<Shader> {
+		// So two threads asking at once don't both create it
+		let _creating = self.creating.lock().unwrap();
+		if let Ok(shader) = sk.shader_find(self.id) {
+			return Ok(shader);
+		}
+		let shader = sk.shader_create_mem(self.data)?;
+		sk.shader_set_id(&shader, self.id);
+		Ok(shader)
+	}
+}
+
+/// Embeds a compiled .sks shader file into the binary, the path is relative
+/// to the current file like `include_bytes!`. Evaluates to a
+/// `&'static EmbeddedShader`, with an asset id of `embedded/<path>` unless
+/// one is given.
+///
+/// ```ignore
+/// let shader = stereokit::include_shader!("../assets/water.hlsl.sks").shader(&sk)?;
+/// let shader = stereokit::include_shader!("app/water", "../assets/water.hlsl.sks").shader(&sk)?;
+/// ```
+#[macro_export]
+macro_rules! include_shader {
+	($path:literal) => {
+		$crate::include_shader!(concat!("embedded/", $path), $path)
+	};
+	($id:expr, $path:literal) => {{
+		static SHADER: $crate::embedded_shader::EmbeddedShader =
+			$crate::embedded_shader::EmbeddedShader::new($id, include_bytes!($path));
+		&SHADER
+	}};
+}
+
+/// Embeds a shader compiled by `shader_build` (the `shader-build` feature) from a
+/// build script, by the name of its .hlsl file without the extension. The
+/// asset id is the same name, unless one is given.
+///
+/// ```ignore
+/// let water = stereokit::include_compiled_shader!("water").shader(&sk)?;
+/// let water = stereokit::include_compiled_shader!("app/water", "water").shader(&sk)?;
+/// ```
+#[macro_export]
+macro_rules! include_compiled_shader {
+	($name:literal) => {
+		$crate::include_compiled_shader!($name, $name)
+	};
+	($id:expr, $name:literal) => {{
+		static SHADER: $crate::embedded_shader::EmbeddedShader =
+			$crate::embedded_shader::EmbeddedShader::new(
+				$id,
+				include_bytes!(concat!(env!("OUT_DIR"), "/shaders/", $name, ".hlsl.sks")),
+			);
+		&SHADER
+	}};
+}
@@ -0,0 +1,538 @@
+use crate::{
+	Material, MaterialParameter, Mesh, Model, ModelNodeId, SkResult, StereoKitError,
+	StereoKitMultiThread, Tex, Vert,
+};
+use glam::Mat4;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+const COMPONENT_UNSIGNED_BYTE: u32 = 5121;
+const COMPONENT_UNSIGNED_INT: u32 = 5125;
+const COMPONENT_FLOAT: u32 = 5126;
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Builds up the json and binary chunk of a GLB file as we walk the model.
+#[derive(Default)]
+struct GltfBuilder {
+	bin: Vec<u8>,
+	buffer_views: Vec<Value>,
+	accessors: Vec<Value>,
+	meshes: Vec<Value>,
+	materials: Vec<Value>,
+	textures: Vec<Value>,
+	images: Vec<Value>,
+	nodes: Vec<Value>,
+	mesh_ids: HashMap<(usize, Option<usize>), usize>,
+	material_ids: HashMap<usize, usize>,
+	texture_ids: HashMap<usize, usize>,
+}
+
+impl GltfBuilder {
+	fn push_view(&mut self, data: &[u8], target: Option<u32>) -> usize {
+		while self.bin.len() % 4 != 0 {
+			self.bin.push(0);
+		}
+		let mut view = json!({
+			"buffer": 0,
+			"byteOffset": self.bin.len(),
+			"byteLength": data.len(),
+		});
+		if let Some(target) = target {
+			view["target"] = json!(target);
+		}
+		self.bin.extend_from_slice(data);
+		self.buffer_views.push(view);
+		self.buffer_views.len() - 1
+	}
+
+	fn push_accessor(
+		&mut self,
+		view: usize,
+		component: u32,
+		count: usize,
+		kind: &str,
+		extra: Value,
+	) -> usize {
+		let mut accessor = json!({
+			"bufferView": view,
+			"componentType": component,
+			"count": count,
+			"type": kind,
+		});
+		if let (Value::Object(accessor), Value::Object(extra)) = (&mut accessor, extra) {
+			accessor.extend(extra);
+		}
+		self.accessors.push(accessor);
+		self.accessors.len() - 1
+	}
+
+	#[cfg(not(feature = "png"))]
+	fn texture(&mut self, _sk: &impl StereoKitMultiThread, _tex: &Tex) -> Option<usize> {
+		None
+	}
+
+	#[cfg(feature = "png")]
+	fn texture(&mut self, sk: &impl StereoKitMultiThread, tex: &Tex) -> Option<usize> {
+		use crate::TextureFormat;
+
+		let key = tex.0.as_ptr() as usize;
+		if let Some(id) = self.texture_ids.get(&key) {
+			return Some(*id);
+		}
+		// Only 8 bit color textures can go into a png without any conversion
+		let format = sk.tex_get_format(tex);
+		let bgra = match format {
+			TextureFormat::RGBA32 | TextureFormat::RGBA32Linear => false,
+			TextureFormat::BGRA32 | TextureFormat::BGRA32Linear => true,
+			_ => return None,
+		};
+		let width = sk.tex_get_width(tex) as u32;
+		let height = sk.tex_get_height(tex) as u32;
+		let mut pixels = sk.tex_get_data(tex);
+		if pixels.is_empty() {
+			return None;
+		}
+		if bgra {
+			pixels
+				.chunks_exact_mut(4)
+				.for_each(|pixel| pixel.swap(0, 2));
+		}
+		let mut png = Vec::new();
+		let mut encoder = png::Encoder::new(&mut png, width, height);
+		encoder.set_color(png::ColorType::Rgba);
+		encoder.set_depth(png::BitDepth::Eight);
+		encoder
+			.write_header()
+			.and_then(|mut writer| writer.write_image_data(&pixels))
+			.ok()?;
+		let view = self.push_view(&png, None);
+		self.images
+			.push(json!({ "bufferView": view, "mimeType": "image/png" }));
+		self.textures
+			.push(json!({ "source": self.images.len() - 1 }));
+		let id = self.textures.len() - 1;
+		self.texture_ids.insert(key, id);
+		Some(id)
+	}
+
+	fn material(&mut self, sk: &impl StereoKitMultiThread, material: &Material) -> usize {
+		let key = material.0.as_ptr() as usize;
+		if let Some(id) = self.material_ids.get(&key) {
+			return *id;
+		}
+		let mut pbr = Map::new();
+		if sk.material_has_param(material, "color", MaterialParameter::Color128) {
+			let color = sk.material_get_color(material, "color");
+			pbr.insert(
+				"baseColorFactor".into(),
+				json!([color.r, color.g, color.b, color.a]),
+			);
+		}
+		if sk.material_has_param(material, "metallic", MaterialParameter::Float) {
+			pbr.insert(
+				"metallicFactor".into(),
+				json!(sk.material_get_float(material, "metallic")),
+			);
+		} else {
+			pbr.insert("metallicFactor".into(), json!(0.0));
+		}
+		if sk.material_has_param(material, "roughness", MaterialParameter::Float) {
+			pbr.insert(
+				"roughnessFactor".into(),
+				json!(sk.material_get_float(material, "roughness")),
+			);
+		}
+		if sk.material_has_param(material, "diffuse", MaterialParameter::Texture) {
+			if let Some(texture) = sk
+				.material_try_get_texture(material, "diffuse")
+				.and_then(|tex| self.texture(sk, &tex))
+			{
+				pbr.insert("baseColorTexture".into(), json!({ "index": texture }));
+			}
+		}
+		self.materials.push(json!({
+			"name": sk.material_get_id(material),
+			"pbrMetallicRoughness": pbr,
+		}));
+		let id = self.materials.len() - 1;
+		self.material_ids.insert(key, id);
+		id
+	}
+
+	fn mesh(
+		&mut self,
+		sk: &impl StereoKitMultiThread,
+		mesh: &Mesh,
+		material: Option<&Material>,
+	) -> SkResult<usize> {
+		let material = material.map(|material| self.material(sk, material));
+		let key = (mesh.0.as_ptr() as usize, material);
+		if let Some(id) = self.mesh_ids.get(&key) {
+			return Ok(*id);
+		}
+		let verts = sk.mesh_get_verts_ref(mesh);
+		let inds = sk.mesh_get_inds_ref(mesh);
+		if verts.is_empty() || inds.is_empty() {
+			return Err(StereoKitError::MeshExport(
+				sk.mesh_get_id(mesh).to_string(),
+				"the mesh has no CPU side data, it needs mesh_set_keep_data".to_string(),
+			));
+		}
+		let id = self.mesh_data(sk.mesh_get_id(mesh), verts, inds, material);
+		self.mesh_ids.insert(key, id);
+		Ok(id)
+	}
+
+	/// Adds a mesh from StereoKit's clockwise wound vertex and index data.
+	fn mesh_data(
+		&mut self,
+		name: &str,
+		verts: &[Vert],
+		inds: &[i32],
+		material: Option<usize>,
+	) -> usize {
+		let mut positions = Vec::with_capacity(verts.len() * 12);
+		let mut normals = Vec::with_capacity(verts.len() * 12);
+		let mut uvs = Vec::with_capacity(verts.len() * 8);
+		let mut colors = Vec::with_capacity(verts.len() * 4);
+		let mut min = verts[0].pos;
+		let mut max = verts[0].pos;
+		for vert in verts {
+			min = min.min(vert.pos);
+			max = max.max(vert.pos);
+			vert.pos
+				.to_array()
+				.iter()
+				.for_each(|v| positions.extend(v.to_le_bytes()));
+			vert.norm
+				.to_array()
+				.iter()
+				.for_each(|v| normals.extend(v.to_le_bytes()));
+			vert.uv
+				.to_array()
+				.iter()
+				.for_each(|v| uvs.extend(v.to_le_bytes()));
+			colors.extend([vert.col.r, vert.col.g, vert.col.b, vert.col.a]);
+		}
+		// StereoKit winds clockwise, glTF front faces are counter-clockwise
+		let indices: Vec<u8> = inds
+			.chunks_exact(3)
+			.flat_map(|tri| [tri[0], tri[2], tri[1]])
+			.flat_map(|i| (i as u32).to_le_bytes())
+			.collect();
+
+		let count = verts.len();
+		let view = self.push_view(&positions, Some(TARGET_ARRAY_BUFFER));
+		let position = self.push_accessor(
+			view,
+			COMPONENT_FLOAT,
+			count,
+			"VEC3",
+			json!({ "min": min.to_array(), "max": max.to_array() }),
+		);
+		let view = self.push_view(&normals, Some(TARGET_ARRAY_BUFFER));
+		let normal = self.push_accessor(view, COMPONENT_FLOAT, count, "VEC3", json!({}));
+		let view = self.push_view(&uvs, Some(TARGET_ARRAY_BUFFER));
+		let uv = self.push_accessor(view, COMPONENT_FLOAT, count, "VEC2", json!({}));
+		let view = self.push_view(&colors, Some(TARGET_ARRAY_BUFFER));
+		let color = self.push_accessor(
+			view,
+			COMPONENT_UNSIGNED_BYTE,
+			count,
+			"VEC4",
+			json!({ "normalized": true }),
+		);
+		let view = self.push_view(&indices, Some(TARGET_ELEMENT_ARRAY_BUFFER));
+		let index = self.push_accessor(
+			view,
+			COMPONENT_UNSIGNED_INT,
+			inds.len(),
+			"SCALAR",
+			json!({}),
+		);
+
+		let mut primitive = json!({
+			"attributes": {
+				"POSITION": position,
+				"NORMAL": normal,
+				"TEXCOORD_0": uv,
+				"COLOR_0": color,
+			},
+			"indices": index,
+		});
+		if let Some(material) = material {
+			primitive["material"] = json!(material);
+		}
+		self.meshes.push(json!({
+			"name": name,
+			"primitives": [primitive],
+		}));
+		self.meshes.len() - 1
+	}
+
+	fn node(
+		&mut self,
+		name: Option<&str>,
+		transform: Mat4,
+		mesh: Option<usize>,
+		extras: Map<String, Value>,
+	) -> usize {
+		let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+		let mut node = json!({
+			"translation": translation.to_array(),
+			"rotation": rotation.to_array(),
+			"scale": scale.to_array(),
+		});
+		if let Some(name) = name {
+			node["name"] = json!(name);
+		}
+		if let Some(mesh) = mesh {
+			node["mesh"] = json!(mesh);
+		}
+		if !extras.is_empty() {
+			node["extras"] = Value::Object(extras);
+		}
+		self.nodes.push(node);
+		self.nodes.len() - 1
+	}
+
+	fn finish(self, roots: Vec<usize>) -> Vec<u8> {
+		let mut scene = Map::new();
+		let mut document = Map::new();
+		document.insert(
+			"asset".into(),
+			json!({ "version": "2.0", "generator": "stereokit-rs" }),
+		);
+		// glTF doesn't allow empty arrays, so anything without items is left out
+		fn insert(map: &mut Map<String, Value>, key: &str, items: Vec<Value>) {
+			if !items.is_empty() {
+				map.insert(key.into(), Value::Array(items));
+			}
+		}
+		insert(
+			&mut scene,
+			"nodes",
+			roots.into_iter().map(Value::from).collect(),
+		);
+		insert(&mut document, "nodes", self.nodes);
+		insert(&mut document, "meshes", self.meshes);
+		insert(&mut document, "materials", self.materials);
+		insert(&mut document, "textures", self.textures);
+		insert(&mut document, "images", self.images);
+		insert(&mut document, "accessors", self.accessors);
+		insert(&mut document, "bufferViews", self.buffer_views);
+		if !self.bin.is_empty() {
+			insert(
+				&mut document,
+				"buffers",
+				vec![json!({ "byteLength": self.bin.len() })],
+			);
+		}
+		document.insert("scene".into(), json!(0));
+		document.insert("scenes".into(), json!([scene]));
+
+		let mut json = serde_json::to_vec(&Value::Object(document)).unwrap();
+		while json.len() % 4 != 0 {
+			json.push(b' ');
+		}
+		let mut bin = self.bin;
+		while bin.len() % 4 != 0 {
+			bin.push(0);
+		}
+
+		// The binary chunk is optional, and left out when there's nothing in it
+		let bin_chunk = if bin.is_empty() { 0 } else { 8 + bin.len() };
+		let total = 12 + 8 + json.len() + bin_chunk;
+		let mut glb = Vec::with_capacity(total);
+		glb.extend(b"glTF");
+		glb.extend(2u32.to_le_bytes());
+		glb.extend((total as u32).to_le_bytes());
+		glb.extend((json.len() as u32).to_le_bytes());
+		glb.extend(b"JSON");
+		glb.extend(json);
+		if !bin.is_empty() {
+			glb.extend((bin.len() as u32).to_le_bytes());
+			glb.extend(b"BIN\0");
+			glb.extend(bin);
+		}
+		glb
+	}
+}
+
+fn node_extras(
+	sk: &impl StereoKitMultiThread,
+	model: &Model,
+	node: ModelNodeId,
+) -> Map<String, Value> {
+	let mut extras = Map::new();
+	let mut iterator = 0;
+	while let Some((key, value, next)) = sk.model_node_info_iterate(model, iterator, node) {
+		extras.insert(key.to_string(), Value::String(value.to_string()));
+		iterator = next;
+	}
+	extras
+}
+
+fn export_node(
+	sk: &impl StereoKitMultiThread,
+	builder: &mut GltfBuilder,
+	model: &Model,
+	node: ModelNodeId,
+) -> SkResult<usize> {
+	let mesh = sk.model_node_get_mesh(model, node);
+	let material = sk.model_node_get_material(model, node);
+	let mesh = mesh
+		.map(|mesh| builder.mesh(sk, &mesh, material.as_ref()))
+		.transpose()?;
+	let id = builder.node(
+		sk.model_node_get_name(model, node),
+		sk.model_node_get_transform_local(model, node),
+		mesh,
+		node_extras(sk, model, node),
+	);
+
+	let mut children = Vec::new();
+	let mut child = sk.model_node_child(model, node);
+	while let Some(child_node) = child {
+		children.push(export_node(sk, builder, model, child_node)?);
+		child = sk.model_node_sibling(model, child_node);
+	}
+	if !children.is_empty() {
+		builder.nodes[id]["children"] = json!(children);
+	}
+	Ok(id)
+}
+
+/// Writes a Model out as a binary glTF (GLB) file in memory! This walks the
+/// whole node tree, and includes each node's mesh, material, local transform
+/// and `model_node_info_*` key/value pairs, which land in the node's glTF
+/// extras. Meshes need to be keeping their data on the CPU to be exported, or
+/// this returns an error, and only 8 bit color textures get embedded, as pngs
+/// with the `png` feature.
+pub fn model_to_glb(sk: &impl StereoKitMultiThread, model: impl AsRef<Model>) -> SkResult<Vec<u8>> {
+	let model = model.as_ref();
+	let mut builder = GltfBuilder::default();
+	let mut roots = Vec::new();
+	let mut node = sk.model_node_get_root(model);
+	while let Some(root) = node {
+		roots.push(export_node(sk, &mut builder, model, root)?);
+		node = sk.model_node_sibling(model, root);
+	}
+	Ok(builder.finish(roots))
+}
+
+/// Writes a Model to a .glb file, see [`model_to_glb`].
+pub fn model_save_glb(
+	sk: &impl StereoKitMultiThread,
+	model: impl AsRef<Model>,
+	file: impl AsRef<Path>,
+) -> SkResult<()> {
+	let file = file.as_ref();
+	std::fs::write(file, model_to_glb(sk, model)?)
+		.map_err(|e| StereoKitError::ExportFile(file.to_path_buf(), e.to_string()))
+}
+
+/// Writes a single Mesh out as a binary glTF (GLB) file in memory, with an
+/// optional Material. The mesh needs to be keeping its data on the CPU.
+pub fn mesh_to_glb(
+	sk: &impl StereoKitMultiThread,
+	mesh: impl AsRef<Mesh>,
+	material: Option<&Material>,
+) -> SkResult<Vec<u8>> {
+	let mut builder = GltfBuilder::default();
+	let mesh = builder.mesh(sk, mesh.as_ref(), material)?;
+	let root = builder.node(None, Mat4::IDENTITY, Some(mesh), Map::new());
+	Ok(builder.finish(vec![root]))
+}
+
+/// Writes a Mesh to a .glb file, see [`mesh_to_glb`].
+pub fn mesh_save_glb(
+	sk: &impl StereoKitMultiThread,
+	mesh: impl AsRef<Mesh>,
+	material: Option<&Material>,
+	file: impl AsRef<Path>,
+) -> SkResult<()> {
+	let file = file.as_ref();
+	std::fs::write(file, mesh_to_glb(sk, mesh, material)?)
+		.map_err(|e| StereoKitError::ExportFile(file.to_path_buf(), e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Color32;
+
+	/// Splits a GLB file into its json, and its binary chunk if it has one.
+	fn read_glb(glb: &[u8]) -> (Value, Option<&[u8]>) {
+		let u32_at = |at: usize| u32::from_le_bytes(glb[at..at + 4].try_into().unwrap()) as usize;
+		assert_eq!(&glb[0..4], b"glTF");
+		assert_eq!(u32_at(4), 2);
+		assert_eq!(u32_at(8), glb.len());
+		assert_eq!(glb.len() % 4, 0);
+		let json_len = u32_at(12);
+		assert_eq!(&glb[16..20], b"JSON");
+		let json = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+		let bin_start = 20 + json_len;
+		if bin_start == glb.len() {
+			return (json, None);
+		}
+		assert_eq!(&glb[bin_start + 4..bin_start + 8], b"BIN\0");
+		let bin_len = u32_at(bin_start);
+		(json, Some(&glb[bin_start + 8..bin_start + 8 + bin_len]))
+	}
+
+	#[test]
+	fn glb_encoding() {
+		let white = Color32 {
+			r: 255,
+			g: 255,
+			b: 255,
+			a: 255,
+		};
+		let verts = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]].map(|[x, y]| Vert {
+			pos: glam::Vec3::new(x, y, 0.0),
+			norm: glam::Vec3::Z,
+			uv: glam::Vec2::new(x, y),
+			col: white,
+		});
+		let mut builder = GltfBuilder::default();
+		// Wound clockwise, the way StereoKit stores it
+		let mesh = builder.mesh_data("tri", &verts, &[0, 1, 2], None);
+		let root = builder.node(Some("root"), Mat4::IDENTITY, Some(mesh), Map::new());
+		let (json, bin) = read_glb(&builder.finish(vec![root]));
+		let bin = bin.unwrap();
+
+		assert_eq!(json["scenes"][0]["nodes"], json!([0]));
+		assert_eq!(json["nodes"][0]["name"], "root");
+		assert_eq!(json["meshes"][0]["name"], "tri");
+		assert_eq!(
+			json["buffers"][0]["byteLength"].as_u64().unwrap() as usize,
+			bin.len()
+		);
+		// Empty arrays aren't allowed in glTF
+		assert!(json.get("materials").is_none());
+		assert!(json.get("textures").is_none());
+
+		let primitive = &json["meshes"][0]["primitives"][0];
+		let accessor = &json["accessors"][primitive["indices"].as_u64().unwrap() as usize];
+		assert_eq!(accessor["count"], 3);
+		let view = &json["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+		let offset = view["byteOffset"].as_u64().unwrap() as usize;
+		let indices: Vec<u32> = bin[offset..offset + 12]
+			.chunks_exact(4)
+			.map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+			.collect();
+		// glTF faces are counter-clockwise
+		assert_eq!(indices, [0, 2, 1]);
+	}
+
+	#[test]
+	fn glb_without_meshes() {
+		let (json, bin) = read_glb(&GltfBuilder::default().finish(Vec::new()));
+		assert!(bin.is_none());
+		assert!(json.get("buffers").is_none());
+		assert!(json.get("nodes").is_none());
+	}
+}
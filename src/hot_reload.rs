@@ -0,0 +1,274 @@
+//! Reloads assets when their files change on disk. Textures and models are
+//! swapped in place, so every handle to them sees the new data. StereoKit
+//! has no way to replace a shader's or a sound's data in place though, so
+//! those aren't: a reloaded shader is a new asset that every Material using
+//! the old one gets pointed at, and a reloaded sound is a new asset that's
+//! handed to a callback. Anything else holding the old shader or sound keeps
+//! the old version.
+
+use crate::{AssetType, Material, Model, Shader, Sound, StereoKitMultiThread, Tex};
+use std::mem::ManuallyDrop;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+enum Watched {
+	Tex {
+		tex: Tex,
+		srgb_data: bool,
+		priority: i32,
+	},
+	Model {
+		model: Model,
+		shader: Option<Shader>,
+	},
+	Shader {
+		shader: Shader,
+	},
+	Sound {
+		on_reload: Box<dyn FnMut(Sound)>,
+	},
+}
+
+impl Drop for Watched {
+	fn drop(&mut self) {
+		// Tex doesn't release itself, the other handles do
+		if let Watched::Tex { tex, .. } = self {
+			unsafe { crate::tex_release(tex) };
+		}
+	}
+}
+
+struct Entry {
+	/// The path as StereoKit sees it, relative to the assets folder.
+	file: PathBuf,
+	/// The path on disk we check for changes.
+	full_path: PathBuf,
+	modified: Option<SystemTime>,
+	reloads: u32,
+	watched: Watched,
+}
+
+/// Watches asset files for changes and reloads them into the handles you
+/// already have, so every holder of a `Tex` or `Model` sees the new data on
+/// the next frame! This only polls file modification times, so it works the
+/// same everywhere without any OS specific file watching.
+///
+/// Shaders can't be swapped in place, so instead every Material that used the
+/// old shader gets pointed at the new one. Sounds can't be swapped either, so
+/// they hand the freshly loaded `Sound` to a callback. See the module docs.
+///
+/// This is opt-in, and meant for development! Call [`HotReload::step`] once per
+/// frame from your run loop.
+pub struct HotReload {
+	assets_folder: PathBuf,
+	entries: Vec<Entry>,
+	/// How often the files get checked, so we're not hitting the disk every frame.
+	pub interval: Duration,
+	last_check: Option<Instant>,
+}
+impl HotReload {
+	/// Watches files relative to this folder, this should match `Settings::assets_folder`.
+	pub fn new(assets_folder: impl AsRef<Path>) -> Self {
+		Self {
+			assets_folder: assets_folder.as_ref().to_path_buf(),
+			entries: Vec::new(),
+			interval: Duration::from_millis(500),
+			last_check: None,
+		}
+	}
+
+	fn add(&mut self, file: impl AsRef<Path>, watched: Watched) {
+		let file = file.as_ref().to_path_buf();
+		let full_path = if file.is_absolute() {
+			file.clone()
+		} else {
+			self.assets_folder.join(&file)
+		};
+		let modified = modified_time(&full_path);
+		self.entries.push(Entry {
+			file,
+			full_path,
+			modified,
+			reloads: 0,
+			watched,
+		});
+	}
+
+	/// Reloads this texture's pixels from `file` whenever it changes, see `tex_create_file`.
+	pub fn watch_tex(
+		&mut self,
+		sk: &impl StereoKitMultiThread,
+		tex: impl AsRef<Tex>,
+		file: impl AsRef<Path>,
+		srgb_data: bool,
+		priority: i32,
+	) {
+		let tex = tex.as_ref();
+		unsafe { sk.tex_addref(tex) };
+		self.add(
+			file,
+			Watched::Tex {
+				tex: Tex(tex.0),
+				srgb_data,
+				priority,
+			},
+		);
+	}
+
+	/// Replaces this model's subsets with the ones from `file` whenever it changes, see `model_create_file`.
+	/// Node hierarchy and animations from the new file aren't carried over, only its visuals.
+	pub fn watch_model(
+		&mut self,
+		sk: &impl StereoKitMultiThread,
+		model: impl AsRef<Model>,
+		file: impl AsRef<Path>,
+		shader: Option<impl AsRef<Shader>>,
+	) {
+		let model = model.as_ref();
+		unsafe { sk.model_addref(model) };
+		let model = Model::from(model.0 .0);
+		let shader = shader.map(|shader| {
+			let shader = shader.as_ref();
+			unsafe { sk.shader_addref(shader) };
+			Shader(shader.0)
+		});
+		self.add(file, Watched::Model { model, shader });
+	}
+
+	/// Points every Material using this shader at a fresh copy from `file` whenever it changes, see `shader_create_file`.
+	pub fn watch_shader(
+		&mut self,
+		sk: &impl StereoKitMultiThread,
+		shader: impl AsRef<Shader>,
+		file: impl AsRef<Path>,
+	) {
+		let shader = shader.as_ref();
+		unsafe { sk.shader_addref(shader) };
+		self.add(
+			file,
+			Watched::Shader {
+				shader: Shader(shader.0),
+			},
+		);
+	}
+
+	/// Calls `on_reload` with a newly created Sound whenever `file` changes, see `sound_create`.
+	pub fn watch_sound(&mut self, file: impl AsRef<Path>, on_reload: impl FnMut(Sound) + 'static) {
+		self.add(
+			file,
+			Watched::Sound {
+				on_reload: Box::new(on_reload),
+			},
+		);
+	}
+
+	/// Stops watching a file, this releases any handles the watcher was holding on to.
+	pub fn unwatch(&mut self, file: impl AsRef<Path>) {
+		let file = file.as_ref();
+		self.entries.retain(|entry| entry.file != file);
+	}
+
+	/// Checks the watched files for changes, and reloads any that were modified.
+	/// Returns the files that were reloaded this call, failures are logged as warnings.
+	pub fn step(&mut self, sk: &impl StereoKitMultiThread) -> Vec<PathBuf> {
+		let now = Instant::now();
+		if let Some(last_check) = self.last_check {
+			if now.duration_since(last_check) < self.interval {
+				return Vec::new();
+			}
+		}
+		self.last_check = Some(now);
+
+		let mut reloaded = Vec::new();
+		for entry in &mut self.entries {
+			let modified = modified_time(&entry.full_path);
+			if modified.is_none() || modified == entry.modified {
+				continue;
+			}
+			entry.modified = modified;
+			match reload(sk, entry) {
+				Ok(()) => reloaded.push(entry.file.clone()),
+				Err(reason) => sk.log_warn(format!(
+					"hot reload of {} failed: {}",
+					entry.full_path.display(),
+					reason
+				)),
+			}
+		}
+		reloaded
+	}
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+	std::fs::metadata(path)
+		.and_then(|meta| meta.modified())
+		.ok()
+}
+
+fn reload(sk: &impl StereoKitMultiThread, entry: &mut Entry) -> Result<(), String> {
+	match &mut entry.watched {
+		Watched::Tex {
+			tex,
+			srgb_data,
+			priority,
+		} => {
+			let data = std::fs::read(&entry.full_path).map_err(|e| e.to_string())?;
+			sk.tex_set_mem(&*tex, &data, *srgb_data, false as i32, *priority);
+		}
+		Watched::Model { model, shader } => {
+			// The _file functions hand back the cached asset with the same id,
+			// so we go through memory to get a fresh copy
+			let data = std::fs::read(&entry.full_path).map_err(|e| e.to_string())?;
+			let new_model = sk
+				.model_create_mem(entry.file.to_string_lossy(), &data, shader.as_ref())
+				.map_err(|e| e.to_string())?;
+			for subset in (0..sk.model_subset_count(&*model)).rev() {
+				sk.model_remove_subset(&*model, subset);
+			}
+			for subset in 0..sk.model_subset_count(&new_model) {
+				let (Some(mesh), Some(material)) = (
+					sk.model_get_mesh(&new_model, subset),
+					sk.model_get_material(&new_model, subset),
+				) else {
+					continue;
+				};
+				sk.model_add_named_subset(
+					&*model,
+					sk.model_get_name(&new_model, subset),
+					mesh,
+					material,
+					sk.model_get_transform(&new_model, subset),
+				);
+			}
+		}
+		Watched::Shader { shader } => {
+			let data = std::fs::read(&entry.full_path).map_err(|e| e.to_string())?;
+			let new_shader = sk.shader_create_mem(&data).map_err(|e| e.to_string())?;
+			for index in 0..sk.assets_count() {
+				if sk.assets_get_type(index) != AssetType::Material {
+					continue;
+				}
+				let Some(asset) = sk.assets_get_index(index) else {
+					continue;
+				};
+				// This is a borrowed reference, so it mustn't release on drop
+				let material = ManuallyDrop::new(Material(asset.0.cast()));
+				if sk.material_get_shader(&*material).0 == shader.0 {
+					sk.material_set_shader(&*material, &new_shader);
+				}
+			}
+			*shader = new_shader;
+		}
+		Watched::Sound { on_reload } => {
+			// Move the old sound out of the way so sound_create doesn't just return it
+			let id = entry.file.to_string_lossy();
+			if let Ok(old) = sk.sound_find(&id) {
+				entry.reloads += 1;
+				sk.sound_set_id(&old, format!("{}/stale{}", id, entry.reloads));
+			}
+			let sound = sk.sound_create(&entry.file).map_err(|e| e.to_string())?;
+			on_reload(sound);
+		}
+	}
+	Ok(())
+}
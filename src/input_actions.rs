@@ -0,0 +1,571 @@
+use crate::{
+	ray_from_mouse, ButtonState, Controller, Hand, Handed, Key, Pose, StereoKitMultiThread,
+};
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A digital button on a physical controller.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ControllerButton {
+	/// 'X' on the left Quest controller, 'A' on the right one.
+	X1,
+	/// 'Y' on the left Quest controller, 'B' on the right one.
+	X2,
+	/// Clicking the analog stick in.
+	StickClick,
+	/// The index finger trigger, pressed once it passes the map's analog threshold.
+	Trigger,
+	/// The grip button, pressed once it passes the map's analog threshold.
+	Grip,
+	/// The menu button, this one isn't tied to a specific hand.
+	Menu,
+}
+
+/// An analog value on a physical controller.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ControllerAxis {
+	/// The index finger trigger, 0 -> 1.
+	Trigger,
+	/// The grip button, 0 -> 1.
+	Grip,
+	/// Horizontal position of the analog stick, -1 -> +1.
+	StickX,
+	/// Vertical position of the analog stick, -1 -> +1.
+	StickY,
+}
+
+/// A gesture StereoKit recognizes on a tracked hand.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HandGesture {
+	/// Finger and thumb together.
+	Pinch,
+	/// Fingers curled next to the palm.
+	Grip,
+}
+
+/// Mouse buttons, these are read through StereoKit's key states.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MouseButton {
+	Left,
+	Right,
+	Center,
+	Forward,
+	Back,
+}
+impl From<MouseButton> for Key {
+	fn from(value: MouseButton) -> Self {
+		match value {
+			MouseButton::Left => Key::MouseLeft,
+			MouseButton::Right => Key::MouseRight,
+			MouseButton::Center => Key::MouseCenter,
+			MouseButton::Forward => Key::MouseForward,
+			MouseButton::Back => Key::MouseBack,
+		}
+	}
+}
+
+/// Analog values coming from the mouse, measured per-frame.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MouseAxis {
+	/// Horizontal movement this frame, in pixels.
+	X,
+	/// Vertical movement this frame, in pixels.
+	Y,
+	/// Scroll wheel change this frame.
+	Scroll,
+}
+
+/// Something that can drive a boolean action.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ButtonBinding {
+	Controller {
+		hand: Handed,
+		button: ControllerButton,
+	},
+	Key(Key),
+	Mouse(MouseButton),
+	Hand {
+		hand: Handed,
+		gesture: HandGesture,
+	},
+}
+
+/// Something that can drive an analog action.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AxisBinding {
+	Controller {
+		hand: Handed,
+		axis: ControllerAxis,
+	},
+	/// The activation percentage of a hand gesture, 0 -> 1.
+	Hand {
+		hand: Handed,
+		gesture: HandGesture,
+	},
+	Mouse(MouseAxis),
+	/// A pair of keys acting as a -1 -> +1 axis, like A/D for strafing.
+	Keys {
+		negative: Key,
+		positive: Key,
+	},
+	/// Any button, reported as 0 or 1.
+	Button(ButtonBinding),
+}
+
+/// Something that can drive a pose action.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PoseBinding {
+	/// The grip pose of a controller, roughly the center of the hand holding it.
+	ControllerGrip(Handed),
+	/// Where a controller is pointing from, and towards.
+	ControllerAim(Handed),
+	/// The palm of a tracked hand.
+	HandPalm(Handed),
+	/// The pinch point of a tracked hand, oriented like the palm.
+	HandPinch(Handed),
+	Head,
+	Eyes,
+	/// A ray from the head through the mouse cursor, only available on flatscreen.
+	MouseRay,
+}
+
+/// A named set of bindings for every action. This is plain serde data, so
+/// profiles can be loaded from and saved to whatever format you like!
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BindingProfile {
+	#[serde(default)]
+	pub buttons: HashMap<String, Vec<ButtonBinding>>,
+	#[serde(default)]
+	pub axes: HashMap<String, Vec<AxisBinding>>,
+	#[serde(default)]
+	pub poses: HashMap<String, Vec<PoseBinding>>,
+}
+
+/// All the input StereoKit has for this frame, so each binding doesn't have
+/// to go back across the FFI boundary.
+struct InputSnapshot {
+	controllers: [Controller; 2],
+	hands: [Hand; 2],
+	menu: ButtonState,
+}
+impl InputSnapshot {
+	fn read(sk: &impl StereoKitMultiThread) -> Self {
+		Self {
+			controllers: [
+				sk.input_controller(Handed::Left),
+				sk.input_controller(Handed::Right),
+			],
+			hands: [sk.input_hand(Handed::Left), sk.input_hand(Handed::Right)],
+			menu: sk.input_controller_menu(),
+		}
+	}
+	fn controller(&self, hand: Handed) -> &Controller {
+		match hand {
+			Handed::Right => &self.controllers[1],
+			_ => &self.controllers[0],
+		}
+	}
+	fn hand(&self, hand: Handed) -> &Hand {
+		match hand {
+			Handed::Right => &self.hands[1],
+			_ => &self.hands[0],
+		}
+	}
+}
+
+/// An action map layer over controllers, hands, keyboard and mouse! Instead
+/// of branching on `input_controller`, `input_hand` and `input_key`
+/// everywhere, name your actions ("jump", "grab", "aim"), bind as many inputs
+/// as you like to each of them, and call [`ActionMap::update`] once per frame.
+///
+/// Boolean actions are active if any of their bindings are, axis actions
+/// report the binding with the largest magnitude, and pose actions report the
+/// first binding that is currently tracked.
+pub struct ActionMap {
+	profile: BindingProfile,
+	/// Trigger and grip values above this count as pressed when bound as buttons.
+	pub analog_threshold: f32,
+	buttons: HashMap<String, ButtonState>,
+	axes: HashMap<String, f32>,
+	poses: HashMap<String, Pose>,
+	/// Trigger and grip values of each controller from the previous update,
+	/// and from this one, so captures can look for the moment they cross the
+	/// threshold.
+	prev_analog: [(f32, f32); 2],
+	analog: [(f32, f32); 2],
+}
+impl Default for ActionMap {
+	fn default() -> Self {
+		Self::from_profile(BindingProfile::default())
+	}
+}
+impl ActionMap {
+	pub fn new() -> Self {
+		Self::default()
+	}
+	pub fn from_profile(profile: BindingProfile) -> Self {
+		Self {
+			profile,
+			analog_threshold: 0.5,
+			buttons: HashMap::new(),
+			axes: HashMap::new(),
+			poses: HashMap::new(),
+			prev_analog: [(0.0, 0.0); 2],
+			analog: [(0.0, 0.0); 2],
+		}
+	}
+
+	/// The bindings currently in use, handy for saving out a player's rebinds.
+	pub fn profile(&self) -> &BindingProfile {
+		&self.profile
+	}
+	/// Swaps every binding out for the ones in this profile. Action states are
+	/// kept, so a button held during the swap won't report a new press.
+	pub fn load_profile(&mut self, profile: BindingProfile) {
+		self.profile = profile;
+	}
+
+	/// Adds a binding to a boolean action, creating the action if it doesn't exist yet.
+	pub fn bind_button(&mut self, action: impl AsRef<str>, binding: ButtonBinding) -> &mut Self {
+		self.profile
+			.buttons
+			.entry(action.as_ref().to_string())
+			.or_default()
+			.push(binding);
+		self
+	}
+	/// Adds a binding to an axis action, creating the action if it doesn't exist yet.
+	pub fn bind_axis(&mut self, action: impl AsRef<str>, binding: AxisBinding) -> &mut Self {
+		self.profile
+			.axes
+			.entry(action.as_ref().to_string())
+			.or_default()
+			.push(binding);
+		self
+	}
+	/// Adds a binding to a pose action, creating the action if it doesn't exist yet.
+	/// Earlier bindings take priority over later ones.
+	pub fn bind_pose(&mut self, action: impl AsRef<str>, binding: PoseBinding) -> &mut Self {
+		self.profile
+			.poses
+			.entry(action.as_ref().to_string())
+			.or_default()
+			.push(binding);
+		self
+	}
+
+	/// Replaces all bindings of a boolean action.
+	pub fn rebind_button(&mut self, action: impl AsRef<str>, bindings: Vec<ButtonBinding>) {
+		self.profile
+			.buttons
+			.insert(action.as_ref().to_string(), bindings);
+	}
+	/// Replaces all bindings of an axis action.
+	pub fn rebind_axis(&mut self, action: impl AsRef<str>, bindings: Vec<AxisBinding>) {
+		self.profile
+			.axes
+			.insert(action.as_ref().to_string(), bindings);
+	}
+	/// Replaces all bindings of a pose action.
+	pub fn rebind_pose(&mut self, action: impl AsRef<str>, bindings: Vec<PoseBinding>) {
+		self.profile
+			.poses
+			.insert(action.as_ref().to_string(), bindings);
+	}
+	/// Removes an action of any kind, along with all of its bindings.
+	pub fn remove_action(&mut self, action: impl AsRef<str>) {
+		let action = action.as_ref();
+		self.profile.buttons.remove(action);
+		self.profile.axes.remove(action);
+		self.profile.poses.remove(action);
+		self.buttons.remove(action);
+		self.axes.remove(action);
+		self.poses.remove(action);
+	}
+
+	/// Reads the input for this frame and updates every action. Call this once
+	/// per frame, before querying any actions.
+	pub fn update(&mut self, sk: &impl StereoKitMultiThread) {
+		let input = InputSnapshot::read(sk);
+		self.prev_analog = self.analog;
+		self.analog = input
+			.controllers
+			.each_ref()
+			.map(|controller| (controller.trigger, controller.grip));
+
+		for (name, bindings) in &self.profile.buttons {
+			let active = bindings
+				.iter()
+				.any(|binding| self.button_binding_active(sk, &input, *binding));
+			let was_active = self
+				.buttons
+				.get(name)
+				.map(|state| state.contains(ButtonState::ACTIVE))
+				.unwrap_or(false);
+			let state = match (was_active, active) {
+				(false, true) => ButtonState::ACTIVE | ButtonState::JUST_ACTIVE,
+				(true, true) => ButtonState::ACTIVE,
+				(true, false) => ButtonState::JUST_INACTIVE,
+				(false, false) => ButtonState::INACTIVE,
+			};
+			self.buttons.insert(name.clone(), state);
+		}
+		self.buttons
+			.retain(|name, _| self.profile.buttons.contains_key(name));
+
+		for (name, bindings) in &self.profile.axes {
+			let value = bindings
+				.iter()
+				.map(|binding| self.axis_binding_value(sk, &input, *binding))
+				.fold(
+					0.0f32,
+					|acc, value| if value.abs() > acc.abs() { value } else { acc },
+				);
+			self.axes.insert(name.clone(), value);
+		}
+		self.axes
+			.retain(|name, _| self.profile.axes.contains_key(name));
+
+		self.poses.clear();
+		for (name, bindings) in &self.profile.poses {
+			if let Some(pose) = bindings
+				.iter()
+				.find_map(|binding| Self::pose_binding_value(sk, &input, *binding))
+			{
+				self.poses.insert(name.clone(), pose);
+			}
+		}
+	}
+
+	/// The full state of a boolean action, including if it was just pressed or released this frame.
+	pub fn button(&self, action: impl AsRef<str>) -> ButtonState {
+		self.buttons
+			.get(action.as_ref())
+			.copied()
+			.unwrap_or(ButtonState::INACTIVE)
+	}
+	/// Is any binding of this action currently held?
+	pub fn is_active(&self, action: impl AsRef<str>) -> bool {
+		self.button(action).contains(ButtonState::ACTIVE)
+	}
+	/// Did this action become active this frame?
+	pub fn just_active(&self, action: impl AsRef<str>) -> bool {
+		self.button(action).contains(ButtonState::JUST_ACTIVE)
+	}
+	/// Did this action stop being active this frame?
+	pub fn just_inactive(&self, action: impl AsRef<str>) -> bool {
+		self.button(action).contains(ButtonState::JUST_INACTIVE)
+	}
+	/// The value of an axis action, 0 if the action doesn't exist.
+	pub fn axis(&self, action: impl AsRef<str>) -> f32 {
+		self.axes.get(action.as_ref()).copied().unwrap_or(0.0)
+	}
+	/// The pose of a pose action, or None if none of its bindings are tracked right now.
+	pub fn pose(&self, action: impl AsRef<str>) -> Option<Pose> {
+		self.poses.get(action.as_ref()).copied()
+	}
+
+	/// Finds the first button-like input that was pressed this frame. This is
+	/// great for "press a button to rebind" menus! Call it after
+	/// [`ActionMap::update`], since triggers and grips only count on the frame
+	/// they cross `analog_threshold`, compared against the previous update.
+	pub fn capture_button(&self, sk: &impl StereoKitMultiThread) -> Option<ButtonBinding> {
+		let input = InputSnapshot::read(sk);
+		let hands = [Handed::Left, Handed::Right];
+		for (hand, (prev_trigger, prev_grip)) in hands.into_iter().zip(self.prev_analog) {
+			let controller = input.controller(hand);
+			let buttons = [
+				(ControllerButton::X1, controller.x1),
+				(ControllerButton::X2, controller.x2),
+				(ControllerButton::StickClick, controller.stick_click),
+			];
+			for (button, state) in buttons {
+				if state.contains(ButtonState::JUST_ACTIVE) {
+					return Some(ButtonBinding::Controller { hand, button });
+				}
+			}
+			if controller.trigger > self.analog_threshold && prev_trigger <= self.analog_threshold {
+				return Some(ButtonBinding::Controller {
+					hand,
+					button: ControllerButton::Trigger,
+				});
+			}
+			if controller.grip > self.analog_threshold && prev_grip <= self.analog_threshold {
+				return Some(ButtonBinding::Controller {
+					hand,
+					button: ControllerButton::Grip,
+				});
+			}
+			let tracked_hand = input.hand(hand);
+			if tracked_hand.pinch_state.contains(ButtonState::JUST_ACTIVE) {
+				return Some(ButtonBinding::Hand {
+					hand,
+					gesture: HandGesture::Pinch,
+				});
+			}
+			if tracked_hand.grip_state.contains(ButtonState::JUST_ACTIVE) {
+				return Some(ButtonBinding::Hand {
+					hand,
+					gesture: HandGesture::Grip,
+				});
+			}
+		}
+		if input.menu.contains(ButtonState::JUST_ACTIVE) {
+			return Some(ButtonBinding::Controller {
+				hand: Handed::Left,
+				button: ControllerButton::Menu,
+			});
+		}
+		let mouse_buttons = [
+			MouseButton::Left,
+			MouseButton::Right,
+			MouseButton::Center,
+			MouseButton::Forward,
+			MouseButton::Back,
+		];
+		for button in mouse_buttons {
+			if sk
+				.input_key(button.into())
+				.contains(ButtonState::JUST_ACTIVE)
+			{
+				return Some(ButtonBinding::Mouse(button));
+			}
+		}
+		// Mouse buttons live at the bottom of the key range, so skip past them
+		(7..=255u32)
+			.filter_map(|code| Key::try_from(code).ok())
+			.find(|key| sk.input_key(*key).contains(ButtonState::JUST_ACTIVE))
+			.map(ButtonBinding::Key)
+	}
+
+	fn button_binding_active(
+		&self,
+		sk: &impl StereoKitMultiThread,
+		input: &InputSnapshot,
+		binding: ButtonBinding,
+	) -> bool {
+		match binding {
+			ButtonBinding::Controller { hand, button } => {
+				let controller = input.controller(hand);
+				match button {
+					ControllerButton::X1 => controller.x1.contains(ButtonState::ACTIVE),
+					ControllerButton::X2 => controller.x2.contains(ButtonState::ACTIVE),
+					ControllerButton::StickClick => {
+						controller.stick_click.contains(ButtonState::ACTIVE)
+					}
+					ControllerButton::Trigger => controller.trigger > self.analog_threshold,
+					ControllerButton::Grip => controller.grip > self.analog_threshold,
+					ControllerButton::Menu => input.menu.contains(ButtonState::ACTIVE),
+				}
+			}
+			ButtonBinding::Key(key) => sk.input_key(key).contains(ButtonState::ACTIVE),
+			ButtonBinding::Mouse(button) => {
+				sk.input_key(button.into()).contains(ButtonState::ACTIVE)
+			}
+			ButtonBinding::Hand { hand, gesture } => {
+				let hand = input.hand(hand);
+				hand.tracked_state.contains(ButtonState::ACTIVE)
+					&& match gesture {
+						HandGesture::Pinch => hand.pinch_state.contains(ButtonState::ACTIVE),
+						HandGesture::Grip => hand.grip_state.contains(ButtonState::ACTIVE),
+					}
+			}
+		}
+	}
+
+	fn axis_binding_value(
+		&self,
+		sk: &impl StereoKitMultiThread,
+		input: &InputSnapshot,
+		binding: AxisBinding,
+	) -> f32 {
+		match binding {
+			AxisBinding::Controller { hand, axis } => {
+				let controller = input.controller(hand);
+				match axis {
+					ControllerAxis::Trigger => controller.trigger,
+					ControllerAxis::Grip => controller.grip,
+					ControllerAxis::StickX => controller.stick.x,
+					ControllerAxis::StickY => controller.stick.y,
+				}
+			}
+			AxisBinding::Hand { hand, gesture } => {
+				let hand = input.hand(hand);
+				if !hand.tracked_state.contains(ButtonState::ACTIVE) {
+					return 0.0;
+				}
+				match gesture {
+					HandGesture::Pinch => hand.pinch_activation,
+					HandGesture::Grip => hand.grip_activation,
+				}
+			}
+			AxisBinding::Mouse(axis) => {
+				let mouse = sk.input_mouse();
+				if !mouse.available {
+					return 0.0;
+				}
+				match axis {
+					MouseAxis::X => mouse.pos_change.x,
+					MouseAxis::Y => mouse.pos_change.y,
+					MouseAxis::Scroll => mouse.scroll_change,
+				}
+			}
+			AxisBinding::Keys { negative, positive } => {
+				let held = |key| sk.input_key(key).contains(ButtonState::ACTIVE) as i32 as f32;
+				held(positive) - held(negative)
+			}
+			AxisBinding::Button(button) => {
+				self.button_binding_active(sk, input, button) as i32 as f32
+			}
+		}
+	}
+
+	fn pose_binding_value(
+		sk: &impl StereoKitMultiThread,
+		input: &InputSnapshot,
+		binding: PoseBinding,
+	) -> Option<Pose> {
+		match binding {
+			PoseBinding::ControllerGrip(hand) => {
+				let controller = input.controller(hand);
+				controller
+					.tracked
+					.contains(ButtonState::ACTIVE)
+					.then_some(controller.pose)
+			}
+			PoseBinding::ControllerAim(hand) => {
+				let controller = input.controller(hand);
+				controller
+					.tracked
+					.contains(ButtonState::ACTIVE)
+					.then_some(controller.aim)
+			}
+			PoseBinding::HandPalm(hand) => {
+				let hand = input.hand(hand);
+				hand.tracked_state
+					.contains(ButtonState::ACTIVE)
+					.then_some(hand.palm)
+			}
+			PoseBinding::HandPinch(hand) => {
+				let hand = input.hand(hand);
+				hand.tracked_state
+					.contains(ButtonState::ACTIVE)
+					.then(|| Pose::new(hand.pinch_pt, hand.palm.orientation))
+			}
+			PoseBinding::Head => Some(sk.input_head()),
+			PoseBinding::Eyes => Some(sk.input_eyes()),
+			PoseBinding::MouseRay => {
+				let mouse = sk.input_mouse();
+				if !mouse.available {
+					return None;
+				}
+				let ray = ray_from_mouse(mouse.pos)?;
+				Some(Pose::new(
+					ray.pos,
+					Quat::from_rotation_arc(Vec3::NEG_Z, ray.dir.normalize()),
+				))
+			}
+		}
+	}
+}
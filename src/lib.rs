@@ -1,5 +1,30 @@
 #![doc = include_str!("../README.md")]
+pub mod assets;
+#[cfg(feature = "bevy")]
+pub mod bevy;
+pub mod camera;
+pub mod capture;
+pub mod curves;
+pub mod debug_draw;
+pub mod embedded_shader;
+pub mod gltf_export;
+pub mod hot_reload;
+pub mod input_actions;
+pub mod material_buffer;
+pub mod material_desc;
+pub mod mesh_export;
+pub mod mirror;
+pub mod model_node;
 pub mod named_colors;
+pub mod post_process;
+pub mod rich_text;
+pub mod scene;
+#[cfg(feature = "shader-build")]
+pub mod shader_build;
+pub mod simulator;
+pub mod sprite_sheet;
+pub mod timing;
+pub mod tween;
 #[cfg(test)]
 mod tests;
 
@@ -33,6 +58,7 @@ use stereokit_sys::{_font_t, _gradient_t, _material_buffer_t, _material_t, _mesh
 use thiserror::Error;
 
 pub use stereokit_sys as sys;
+pub use bytemuck;
 
 pub struct SkDraw(PhantomData<*const ()>);
 #[cfg_attr(feature = "bevy_ecs", derive(bevy_ecs::prelude::Resource))]
@@ -61,6 +87,10 @@ impl Sk {
 	pub unsafe fn create_unsafe() -> Sk {
 		Sk(PhantomData)
 	}
+	/// The multithreaded handle, from any other handle, for storing in callbacks that can't be generic.
+	pub(crate) fn from_handle(_sk: &impl StereoKitMultiThread) -> Sk {
+		Sk(PhantomData)
+	}
 }
 
 impl SkDraw {
@@ -244,6 +274,10 @@ pub enum StereoKitError {
 	FontFind(String, String),
 	#[error("failed to create font from file {0} for reason {1}")]
 	FontFile(PathBuf, String),
+	#[error("failed to create font from files {0:?} for reason {1}")]
+	FontFiles(Vec<PathBuf>, String),
+	#[error("failed to create font from memory for reason {0}")]
+	FontMem(String),
 	#[error("failed to find shader {0} for reason {1}")]
 	ShaderFind(String, String),
 	#[error("failed to create shader from file {0} for reason {1}")]
@@ -252,10 +286,16 @@ pub enum StereoKitError {
 	ShaderMem,
 	#[error("failed to find material {0} for reason {1}")]
 	MaterialFind(String, String),
+	#[error("failed to parse material description for reason {0}")]
+	MaterialParse(String),
+	#[error("failed to read or write material file {0} for reason {1}")]
+	MaterialFile(PathBuf, String),
 	#[error("failed to create sprite from texture")]
 	SpriteCreate,
 	#[error("failed to create sprite from file {0}")]
 	SpriteFile(String),
+	#[error("failed to slice sprite sheet for reason {0}")]
+	SpriteSheet(String),
 	#[error("failed to find sprite {0} for reason {1}")]
 	SpriteFind(String, String),
 	#[error("failed to find sound {0}")]
@@ -264,11 +304,33 @@ pub enum StereoKitError {
 	SoundCreate(PathBuf),
 	#[error("failed to init stereokit with settings {0}")]
 	SkInit(Settings),
+	#[error("failed to load asset {0} with state {1:?}")]
+	AssetLoad(PathBuf, AssetState),
+	#[error("failed to parse scene for reason {0}")]
+	SceneParse(String),
+	#[error("failed to read or write scene file {0} for reason {1}")]
+	SceneFile(PathBuf, String),
+	#[error("failed to write export file {0} for reason {1}")]
+	ExportFile(PathBuf, String),
+	#[error("failed to export mesh {0} for reason {1}")]
+	MeshExport(String, String),
+	#[error("failed to convert model node info {0} for reason {1}")]
+	NodeInfo(String, String),
+	#[error("failed to capture render for reason {0}")]
+	Capture(String),
+	#[error("failed to encode image for reason {0}")]
+	Encode(String),
 }
 
 pub type Color32 = stereokit_sys::color32;
 pub type Color128 = stereokit_sys::color128;
-pub type ModelNodeId = i32;
+
+/// The id of a node in a Model's hierarchy. This is only meaningful for the
+/// Model it came from, see [`model_node::ModelNode`] for a handle that keeps
+/// the two together.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct ModelNodeId(pub(crate) i32);
 
 /// Specifies a type of display mode StereoKit uses, like Mixed Reality headset display vs. a PC display, or even just rendering to an offscreen surface, or not rendering at all!
 #[derive(Debug, Copy, Clone, Deserialize_repr, Serialize_repr, PartialEq, Eq)]
@@ -1278,8 +1340,156 @@ impl AsRef<Asset> for Model {
 	}
 }
 
+/// A typed handle to any asset StereoKit is currently holding on to! You'll get these from assets_get_any, assets_iter and assets_find_any, and you can turn them back into the specific asset type with TryFrom or the as_ functions.
+///
+/// Most variants hold their own reference, but Tex and Solid don't release on drop, so those two are borrowed: they're only valid for as long as something else keeps the asset alive.
+pub enum AnyAsset {
+	Mesh(Mesh),
+	Tex(Tex),
+	Shader(Shader),
+	Material(Material),
+	Model(Model),
+	Font(Font),
+	Sprite(Sprite),
+	Sound(Sound),
+	Solid(Solid),
+}
+
+macro_rules! any_asset_variant {
+	($name: ident, $as_fn: ident) => {
+		impl From<$name> for AnyAsset {
+			fn from(value: $name) -> Self {
+				AnyAsset::$name(value)
+			}
+		}
+		impl TryFrom<AnyAsset> for $name {
+			type Error = AnyAsset;
+			fn try_from(value: AnyAsset) -> Result<Self, Self::Error> {
+				match value {
+					AnyAsset::$name(asset) => Ok(asset),
+					other => Err(other),
+				}
+			}
+		}
+		impl AnyAsset {
+			pub fn $as_fn(&self) -> Option<&$name> {
+				match self {
+					AnyAsset::$name(asset) => Some(asset),
+					_ => None,
+				}
+			}
+		}
+	};
+}
+any_asset_variant!(Mesh, as_mesh);
+any_asset_variant!(Tex, as_tex);
+any_asset_variant!(Shader, as_shader);
+any_asset_variant!(Material, as_material);
+any_asset_variant!(Model, as_model);
+any_asset_variant!(Font, as_font);
+any_asset_variant!(Sprite, as_sprite);
+any_asset_variant!(Sound, as_sound);
+any_asset_variant!(Solid, as_solid);
+
+impl AnyAsset {
+	/// Wraps the asset at this pointer, adding a reference for the handle types that release on drop.
+	/// Tex and Solid don't, so those are borrowed and no reference is added for them.
+	unsafe fn from_raw(ptr: NonNull<c_void>, asset_type: AssetType) -> Option<Self> {
+		Some(match asset_type {
+			AssetType::None => return None,
+			AssetType::Mesh => {
+				stereokit_sys::mesh_addref(ptr.as_ptr() as _);
+				AnyAsset::Mesh(Mesh(ptr.cast()))
+			}
+			AssetType::Tex => AnyAsset::Tex(Tex(ptr.cast())),
+			AssetType::Shader => {
+				stereokit_sys::shader_addref(ptr.as_ptr() as _);
+				AnyAsset::Shader(Shader(ptr.cast()))
+			}
+			AssetType::Material => {
+				stereokit_sys::material_addref(ptr.as_ptr() as _);
+				AnyAsset::Material(Material(ptr.cast()))
+			}
+			AssetType::Model => {
+				stereokit_sys::model_addref(ptr.as_ptr() as _);
+				AnyAsset::Model(Model::from(ptr.cast()))
+			}
+			AssetType::Font => {
+				stereokit_sys::font_addref(ptr.as_ptr() as _);
+				AnyAsset::Font(Font(ptr.cast()))
+			}
+			AssetType::Sprite => {
+				stereokit_sys::sprite_addref(ptr.as_ptr() as _);
+				AnyAsset::Sprite(Sprite(ptr.cast()))
+			}
+			AssetType::Sound => {
+				stereokit_sys::sound_addref(ptr.as_ptr() as _);
+				AnyAsset::Sound(Sound(ptr.cast()))
+			}
+			AssetType::Solid => AnyAsset::Solid(Solid(ptr.cast())),
+		})
+	}
+
+	pub fn asset_type(&self) -> AssetType {
+		match self {
+			AnyAsset::Mesh(_) => AssetType::Mesh,
+			AnyAsset::Tex(_) => AssetType::Tex,
+			AnyAsset::Shader(_) => AssetType::Shader,
+			AnyAsset::Material(_) => AssetType::Material,
+			AnyAsset::Model(_) => AssetType::Model,
+			AnyAsset::Font(_) => AssetType::Font,
+			AnyAsset::Sprite(_) => AssetType::Sprite,
+			AnyAsset::Sound(_) => AssetType::Sound,
+			AnyAsset::Solid(_) => AssetType::Solid,
+		}
+	}
+}
+
+impl AsRef<Asset> for AnyAsset {
+	fn as_ref(&self) -> &Asset {
+		unsafe {
+			match self {
+				AnyAsset::Mesh(asset) => std::mem::transmute(asset),
+				AnyAsset::Tex(asset) => std::mem::transmute(asset),
+				AnyAsset::Shader(asset) => std::mem::transmute(asset),
+				AnyAsset::Material(asset) => std::mem::transmute(asset),
+				AnyAsset::Model(asset) => std::mem::transmute(asset),
+				AnyAsset::Font(asset) => std::mem::transmute(asset),
+				AnyAsset::Sprite(asset) => std::mem::transmute(asset),
+				AnyAsset::Sound(asset) => std::mem::transmute(asset),
+				AnyAsset::Solid(asset) => std::mem::transmute(asset),
+			}
+		}
+	}
+}
+
+/// Walks through every asset StereoKit currently has loaded, see assets_iter.
+pub struct AssetIter<'a, S: StereoKitMultiThread> {
+	sk: &'a S,
+	index: i32,
+}
+impl<'a, S: StereoKitMultiThread> Iterator for AssetIter<'a, S> {
+	type Item = AnyAsset;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while self.index < self.sk.assets_count() {
+			let index = self.index;
+			self.index += 1;
+			if let Some(asset) = self.sk.assets_get_any(index) {
+				return Some(asset);
+			}
+		}
+		None
+	}
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct TextStyle(pub u32);
+impl TextStyle {
+	/// StereoKit's built in style, the default font and text material. It's
+	/// always the first style made, so it's there without any setup.
+	pub const DEFAULT: TextStyle = TextStyle(0);
+}
 /// A enum for describing alignment or positioning
 #[derive(Debug, Copy, Clone, Serialize_repr, Deserialize_repr, PartialEq, Eq)]
 #[repr(u32)]
@@ -1409,8 +1619,8 @@ impl Into<vert_t> for Vert {
 }
 
 ///Culling is discarding an object from the render pipeline! This enum describes how mesh faces get discarded on the graphics card. With culling set to none, you can double the number of pixels the GPU ends up drawing, which can have a big impact on performance. None can be appropriate in cases where the mesh is designed to be ‘double sided’. Front can also be helpful when you want to flip a mesh ‘inside-out’!
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-#[repr(C)]
+#[derive(Debug, Copy, Clone, Deserialize_repr, Serialize_repr, PartialEq, Eq)]
+#[repr(u32)]
 pub enum CullMode {
 	/// Discard if the back of the triangle face is pointing towards the camera. This is the default behavior.
 	Back = 0,
@@ -1514,6 +1724,27 @@ pub enum TextureFormat {
 	/// your far clip in, or switch to 32/24 bit depth.
 	Depth16 = 16,
 }
+impl TextureFormat {
+	/// How many bytes a single pixel of this format takes up.
+	pub fn size(&self) -> usize {
+		match self {
+			TextureFormat::None => 0,
+			TextureFormat::R8 => 1,
+			TextureFormat::R16 | TextureFormat::Depth16 => 2,
+			TextureFormat::RGBA32
+			| TextureFormat::RGBA32Linear
+			| TextureFormat::BGRA32
+			| TextureFormat::BGRA32Linear
+			| TextureFormat::RG11B10
+			| TextureFormat::RGB10A2
+			| TextureFormat::R32
+			| TextureFormat::DepthStencil
+			| TextureFormat::Depth32 => 4,
+			TextureFormat::RGBA64 | TextureFormat::RGBA64S | TextureFormat::RGBA64F => 8,
+			TextureFormat::RGBA128 => 16,
+		}
+	}
+}
 
 /// How does the shader grab pixels from the texture? Or more
 /// specifically, how does the shader grab colors between the provided
@@ -2889,6 +3120,34 @@ pub trait StereoKitSingleThread: StereoKitMultiThread {
 		unsafe { stereokit_sys::hierarchy_to_world_pose(&pose) }.into()
 	}
 }
+/// Writes font data to a file in the temp folder for `font_create_mem`. The name
+/// comes from the process id and a counter, so no two loads ever share a file.
+fn font_temp_file(data: &[u8]) -> SkResult<PathBuf> {
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	static COUNTER: AtomicUsize = AtomicUsize::new(0);
+	let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+	let path = std::env::temp_dir().join(format!("stereokit_font_{}_{}.ttf", std::process::id(), count));
+	std::fs::write(&path, data).map_err(|e| {
+		let _ = std::fs::remove_file(&path);
+		StereoKitError::FontMem(e.to_string())
+	})?;
+	Ok(path)
+}
+
+/// Writes each font to its own temp file, and removes them all again once `create` is done with them.
+fn with_font_temp_files<T>(fonts: &[&[u8]], create: impl FnOnce(&[PathBuf]) -> SkResult<T>) -> SkResult<T> {
+	let mut paths = Vec::with_capacity(fonts.len());
+	let written = fonts.iter().try_for_each(|data| {
+		paths.push(font_temp_file(data)?);
+		Ok(())
+	});
+	let result = written.and_then(|()| create(&paths));
+	for path in &paths {
+		let _ = std::fs::remove_file(path);
+	}
+	result
+}
+
 pub trait StereoKitMultiThread {
 	/// Shuts down all StereoKit initialized systems. Release your own StereoKit created assets before calling this.
 	fn shutdown(&self) {
@@ -3599,6 +3858,12 @@ pub trait StereoKitMultiThread {
 		unsafe { stereokit_sys::tex_addref(tex.as_ref().0.as_ptr()) }
 	}
 
+	/// decrements the reference count, Tex doesn't do this on drop, so this is for textures you
+	/// created and are done with. Any other Tex pointing at the same texture must not be used after.
+	unsafe fn tex_release(&self, tex: Tex) {
+		unsafe { stereokit_sys::tex_release(tex.0.as_ptr()) }
+	}
+
 	/// Textures are loaded asyncronously, so this tells you the current state of this texture! This also can tell if an error occured, and what type of error it may have been.
 	fn tex_asset_state<T: AsRef<Tex>>(&self, tex: T) -> AssetState {
 		unsafe { std::mem::transmute(stereokit_sys::tex_asset_state(tex.as_ref().0.as_ptr())) }
@@ -3608,7 +3873,22 @@ pub trait StereoKitMultiThread {
 
 	//TODO: tex_on_load_remove
 
-	//TODO: tex_set_colors, need to use width*height checking
+	/// Sets the texture's size and pixel data, in the texture's format. Panics if
+	/// `data` isn't width * height pixels long, see TextureFormat::size.
+	fn tex_set_colors<T: AsRef<Tex>>(&self, tex: T, width: i32, height: i32, data: &[u8]) {
+		let tex = tex.as_ref();
+		assert!(width >= 0 && height >= 0, "tex_set_colors size can't be negative");
+		let size = width as usize * height as usize * self.tex_get_format(tex).size();
+		assert_eq!(data.len(), size, "tex_set_colors data is the wrong size");
+		unsafe {
+			stereokit_sys::tex_set_colors(
+				tex.0.as_ptr(),
+				width,
+				height,
+				data.as_ptr() as *mut c_void,
+			)
+		}
+	}
 
 	//TODO: tex_set_color_arr
 
@@ -3633,16 +3913,18 @@ pub trait StereoKitMultiThread {
 		.unwrap())
 	}
 
-	//TODO: this is wrong, we need to allocate a buffer and insert it
-	// it is width * height * sizeof<color_format>
-	// fn tex_get_data<T: AsRef<Tex>>(&self, tex: T) -> &mut [u8] {
-	//     let ptr = null_mut();
-	//     let mut size = 0;
-	//     unsafe {
-	//         stereokit_sys::tex_get_data(tex.as_ref().0.as_ptr(), ptr, size);
-	//         &mut *slice_from_raw_parts_mut(std::mem::transmute(ptr), size)
-	//     }
-	// }
+	/// Retrieve the color data of the texture from the GPU. This can be a very slow operation, so use it cautiously. The data is width * height pixels, in the texture’s format, see TextureFormat::size.
+	fn tex_get_data<T: AsRef<Tex>>(&self, tex: T) -> Vec<u8> {
+		let tex = tex.as_ref();
+		let size = self.tex_get_width(tex) as usize
+			* self.tex_get_height(tex) as usize
+			* self.tex_get_format(tex).size();
+		let mut data = vec![0u8; size];
+		unsafe {
+			stereokit_sys::tex_get_data(tex.0.as_ptr(), data.as_mut_ptr() as *mut c_void, size)
+		}
+		data
+	}
 
 	//TODO: tex_get_data_mip
 
@@ -3824,6 +4106,61 @@ pub trait StereoKitMultiThread {
 		))
 	}
 
+	/// Loads a font made from several font files, if a character isn't in the
+	/// first font it falls back to the next one, and so on. This is how you get
+	/// Latin, CJK and emoji text all from one Font!
+	fn font_create_files(&self, files_utf8: &[impl AsRef<Path>]) -> SkResult<Font> {
+		let paths: Vec<PathBuf> = files_utf8
+			.iter()
+			.map(|f| f.as_ref().to_path_buf())
+			.collect();
+		if paths.is_empty() {
+			return Err(StereoKitError::FontFiles(
+				paths,
+				"no files given".to_string(),
+			));
+		}
+		let c_strs = paths
+			.iter()
+			.map(|path| path.to_str().and_then(|s| CString::new(s).ok()))
+			.collect::<Option<Vec<_>>>()
+			.ok_or_else(|| {
+				StereoKitError::FontFiles(paths.clone(), "CString conversion".to_string())
+			})?;
+		let mut c_ptrs: Vec<*const std::os::raw::c_char> =
+			c_strs.iter().map(|s| s.as_ptr()).collect();
+		Ok(Font(
+			NonNull::new(unsafe {
+				stereokit_sys::font_create_files(c_ptrs.as_mut_ptr(), c_ptrs.len() as i32)
+			})
+			.ok_or(StereoKitError::FontFiles(
+				paths.clone(),
+				"font_create_files failed".to_string(),
+			))?,
+		))
+	}
+
+	/// Creates a font from the bytes of a .ttf or .otf file, like one embedded
+	/// with `include_bytes!`. StereoKit only loads fonts from files, so the bytes
+	/// get written to a uniquely named file in the temp folder, which is deleted
+	/// again as soon as StereoKit has loaded it. Since the id comes from that
+	/// file's name, every call creates a new Font, even for the same bytes.
+	fn font_create_mem(&self, data: &[u8]) -> SkResult<Font> {
+		with_font_temp_files(&[data], |paths| {
+			self.font_create(&paths[0])
+				.map_err(|e| StereoKitError::FontMem(e.to_string()))
+		})
+	}
+
+	/// Like `font_create_files`, but for a fallback chain of fonts in memory,
+	/// see `font_create_mem` for how the temp files are handled.
+	fn font_create_mem_files(&self, fonts: &[&[u8]]) -> SkResult<Font> {
+		with_font_temp_files(fonts, |paths| {
+			self.font_create_files(paths)
+				.map_err(|e| StereoKitError::FontMem(e.to_string()))
+		})
+	}
+
 	fn font_set_id<S: Into<String> + Clone>(&self, font: impl AsRef<Font>, id: S) {
 		let c_str = CString::new(id.into()).unwrap();
 		unsafe { stereokit_sys::font_set_id(font.as_ref().0.as_ptr(), c_str.as_ptr()) }
@@ -4378,6 +4715,11 @@ pub trait StereoKitMultiThread {
 		}
 	}
 
+	fn material_get_color<M: AsRef<Material>, S: AsRef<str>>(&self, material: M, name: S) -> Color128 {
+		let c_str = CString::new(name.as_ref()).unwrap();
+		unsafe { stereokit_sys::material_get_color(material.as_ref().0.as_ptr(), c_str.as_ptr()) }
+	}
+
 	fn material_get_float<M: AsRef<Material>, S: AsRef<str>>(&self, material: M, name: S) -> f32 {
 		let c_str = CString::new(name.as_ref()).unwrap();
 		unsafe { stereokit_sys::material_get_float(material.as_ref().0.as_ptr(), c_str.as_ptr()) }
@@ -4444,6 +4786,18 @@ pub trait StereoKitMultiThread {
 		.unwrap())
 	}
 
+	/// Like `material_get_texture`, but returns None if the texture slot hasn't been set.
+	fn material_try_get_texture<M: AsRef<Material>, S: AsRef<str>>(
+		&self,
+		material: M,
+		name: S,
+	) -> Option<Tex> {
+		let c_str = CString::new(name.as_ref()).unwrap();
+		Some(Tex(NonNull::new(unsafe {
+			stereokit_sys::material_get_texture(material.as_ref().0.as_ptr(), c_str.as_ptr())
+		})?))
+	}
+
 	fn material_has_param<M: AsRef<Material>, S: AsRef<str>>(
 		&self,
 		material: M,
@@ -4536,7 +4890,33 @@ pub trait StereoKitMultiThread {
 		}
 	}
 
-	//TODO: material_get_param_info need to figure out how to do this one
+	/// The name and type of the parameter at this index, from 0 up to material_get_param_count. This is how you find every parameter a Material's shader has.
+	fn material_get_param_info<M: AsRef<Material>>(
+		&self,
+		material: M,
+		index: i32,
+	) -> Option<(String, MaterialParameter)> {
+		let material = material.as_ref();
+		if index < 0 || index >= self.material_get_param_count(material) {
+			return None;
+		}
+		let mut name: *mut std::os::raw::c_char = null_mut();
+		let mut type_: stereokit_sys::material_param_ =
+			unsafe { std::mem::transmute(MaterialParameter::Unknown) };
+		unsafe {
+			stereokit_sys::material_get_param_info(
+				material.0.as_ptr(),
+				index,
+				&mut name,
+				&mut type_,
+			)
+		};
+		if name.is_null() {
+			return None;
+		}
+		let name = unsafe { CStr::from_ptr(name) }.to_str().ok()?.to_string();
+		Some((name, unsafe { std::mem::transmute(type_) }))
+	}
 
 	fn material_get_param_count<M: AsRef<Material>>(&self, material: M) -> i32 {
 		unsafe { stereokit_sys::material_get_param_count(material.as_ref().0.as_ptr()) }
@@ -5059,7 +5439,7 @@ pub trait StereoKitMultiThread {
 		material: impl AsRef<Material>,
 	) -> ModelNodeId {
 		let name = CString::new(name.as_ref()).unwrap();
-		unsafe {
+		ModelNodeId(unsafe {
 			stereokit_sys::model_node_add(
 				model.as_ref().0.as_ptr(),
 				name.as_ptr(),
@@ -5068,7 +5448,7 @@ pub trait StereoKitMultiThread {
 				material.as_ref().0.as_ptr(),
 				0,
 			)
-		}
+		})
 	}
 
 	/// Adds a Child node below this node, at the end of the child chain!
@@ -5082,17 +5462,17 @@ pub trait StereoKitMultiThread {
 		material: impl AsRef<Material>,
 	) -> ModelNodeId {
 		let name = CString::new(name.as_ref()).unwrap();
-		unsafe {
+		ModelNodeId(unsafe {
 			stereokit_sys::model_node_add_child(
 				model.as_ref().0.as_ptr(),
-				parent,
+				parent.0,
 				name.as_ptr(),
 				local_transform.into().into(),
 				mesh.as_ref().0.as_ptr(),
 				material.as_ref().0.as_ptr(),
 				0,
 			)
-		}
+		})
 	}
 
 	fn model_node_find<M: AsRef<Model>, S: AsRef<str>>(
@@ -5103,7 +5483,7 @@ pub trait StereoKitMultiThread {
 		let name = CString::new(name.as_ref()).unwrap();
 		match unsafe { stereokit_sys::model_node_find(model.as_ref().0.as_ptr(), name.as_ptr()) } {
 			-1 => None,
-			otherwise => Some(otherwise),
+			otherwise => Some(ModelNodeId(otherwise)),
 		}
 	}
 
@@ -5113,9 +5493,9 @@ pub trait StereoKitMultiThread {
 		model: M,
 		node: ModelNodeId,
 	) -> Option<ModelNodeId> {
-		match unsafe { stereokit_sys::model_node_sibling(model.as_ref().0.as_ptr(), node) } {
+		match unsafe { stereokit_sys::model_node_sibling(model.as_ref().0.as_ptr(), node.0) } {
 			-1 => None,
-			otherwise => Some(otherwise),
+			otherwise => Some(ModelNodeId(otherwise)),
 		}
 	}
 
@@ -5125,9 +5505,9 @@ pub trait StereoKitMultiThread {
 		model: M,
 		node: ModelNodeId,
 	) -> Option<ModelNodeId> {
-		match unsafe { stereokit_sys::model_node_parent(model.as_ref().0.as_ptr(), node) } {
+		match unsafe { stereokit_sys::model_node_parent(model.as_ref().0.as_ptr(), node.0) } {
 			-1 => None,
-			otherwise => Some(otherwise),
+			otherwise => Some(ModelNodeId(otherwise)),
 		}
 	}
 
@@ -5137,9 +5517,9 @@ pub trait StereoKitMultiThread {
 		model: M,
 		node: ModelNodeId,
 	) -> Option<ModelNodeId> {
-		match unsafe { stereokit_sys::model_node_child(model.as_ref().0.as_ptr(), node) } {
+		match unsafe { stereokit_sys::model_node_child(model.as_ref().0.as_ptr(), node.0) } {
 			-1 => None,
-			otherwise => Some(otherwise),
+			otherwise => Some(ModelNodeId(otherwise)),
 		}
 	}
 
@@ -5150,7 +5530,7 @@ pub trait StereoKitMultiThread {
 	fn model_node_index<M: AsRef<Model>>(&self, model: M, index: i32) -> Option<ModelNodeId> {
 		match unsafe { stereokit_sys::model_node_index(model.as_ref().0.as_ptr(), index) } {
 			-1 => None,
-			otherwise => Some(otherwise),
+			otherwise => Some(ModelNodeId(otherwise)),
 		}
 	}
 
@@ -5161,7 +5541,7 @@ pub trait StereoKitMultiThread {
 	fn model_node_visual_index<M: AsRef<Model>>(&self, model: M, index: i32) -> Option<ModelNodeId> {
 		match unsafe { stereokit_sys::model_node_visual_index(model.as_ref().0.as_ptr(), index) } {
 			-1 => None,
-			otherwise => Some(otherwise),
+			otherwise => Some(ModelNodeId(otherwise)),
 		}
 	}
 
@@ -5170,21 +5550,25 @@ pub trait StereoKitMultiThread {
 		model: M,
 		node: ModelNodeId,
 	) -> Option<ModelNodeId> {
-		match unsafe { stereokit_sys::model_node_iterate(model.as_ref().0.as_ptr(), node) } {
+		match unsafe { stereokit_sys::model_node_iterate(model.as_ref().0.as_ptr(), node.0) } {
 			-1 => None,
-			otherwise => Some(otherwise),
+			otherwise => Some(ModelNodeId(otherwise)),
 		}
 	}
 
-	fn model_node_get_root<M: AsRef<Model>>(&self, model: M) -> ModelNodeId {
-		unsafe { stereokit_sys::model_node_get_root(model.as_ref().0.as_ptr()) }
+	/// The first root node of the Model's hierarchy, or None if the Model has no nodes.
+	fn model_node_get_root<M: AsRef<Model>>(&self, model: M) -> Option<ModelNodeId> {
+		match unsafe { stereokit_sys::model_node_get_root(model.as_ref().0.as_ptr()) } {
+			-1 => None,
+			otherwise => Some(ModelNodeId(otherwise)),
+		}
 	}
 
 	fn model_node_get_name<M: AsRef<Model>>(&self, model: M, node: ModelNodeId) -> Option<&str> {
 		unsafe {
 			CStr::from_ptr(stereokit_sys::model_node_get_name(
 				model.as_ref().0.as_ptr(),
-				node,
+				node.0,
 			))
 			.to_str()
 			.map(|s| Some(s))
@@ -5193,11 +5577,11 @@ pub trait StereoKitMultiThread {
 	}
 
 	fn model_node_get_solid<M: AsRef<Model>>(&self, model: M, node: ModelNodeId) -> bool {
-		unsafe { stereokit_sys::model_node_get_solid(model.as_ref().0.as_ptr(), node) != 0 }
+		unsafe { stereokit_sys::model_node_get_solid(model.as_ref().0.as_ptr(), node.0) != 0 }
 	}
 
 	fn model_node_get_visible<M: AsRef<Model>>(&self, model: M, node: ModelNodeId) -> bool {
-		unsafe { stereokit_sys::model_node_get_visible(model.as_ref().0.as_ptr(), node) != 0 }
+		unsafe { stereokit_sys::model_node_get_visible(model.as_ref().0.as_ptr(), node.0) != 0 }
 	}
 
 	fn model_node_get_material<M: AsRef<Model>>(
@@ -5206,23 +5590,23 @@ pub trait StereoKitMultiThread {
 		node: ModelNodeId,
 	) -> Option<Material> {
 		Some(Material(NonNull::new(unsafe {
-			stereokit_sys::model_node_get_material(model.as_ref().0.as_ptr(), node)
+			stereokit_sys::model_node_get_material(model.as_ref().0.as_ptr(), node.0)
 		})?))
 	}
 
 	fn model_node_get_mesh<M: AsRef<Model>>(&self, model: M, node: ModelNodeId) -> Option<Mesh> {
 		Some(Mesh(NonNull::new(unsafe {
-			stereokit_sys::model_node_get_mesh(model.as_ref().0.as_ptr(), node)
+			stereokit_sys::model_node_get_mesh(model.as_ref().0.as_ptr(), node.0)
 		})?))
 	}
 
 	fn model_node_get_transform_model<M: AsRef<Model>>(&self, model: M, node: ModelNodeId) -> Mat4 {
-		unsafe { stereokit_sys::model_node_get_transform_model(model.as_ref().0.as_ptr(), node) }
+		unsafe { stereokit_sys::model_node_get_transform_model(model.as_ref().0.as_ptr(), node.0) }
 			.into()
 	}
 
 	fn model_node_get_transform_local<M: AsRef<Model>>(&self, model: M, node: ModelNodeId) -> Mat4 {
-		unsafe { stereokit_sys::model_node_get_transform_local(model.as_ref().0.as_ptr(), node) }
+		unsafe { stereokit_sys::model_node_get_transform_local(model.as_ref().0.as_ptr(), node.0) }
 			.into()
 	}
 
@@ -5234,7 +5618,7 @@ pub trait StereoKitMultiThread {
 	) {
 		let name = CString::new(name.as_ref()).unwrap();
 		unsafe {
-			stereokit_sys::model_node_set_name(model.as_ref().0.as_ptr(), node, name.as_ptr())
+			stereokit_sys::model_node_set_name(model.as_ref().0.as_ptr(), node.0, name.as_ptr())
 		}
 	}
 
@@ -5242,7 +5626,7 @@ pub trait StereoKitMultiThread {
 		unsafe {
 			stereokit_sys::model_node_set_solid(
 				model.as_ref().0.as_ptr(),
-				node,
+				node.0,
 				solid as bool32_t,
 			)
 		}
@@ -5252,7 +5636,7 @@ pub trait StereoKitMultiThread {
 		unsafe {
 			stereokit_sys::model_node_set_visible(
 				model.as_ref().0.as_ptr(),
-				node,
+				node.0,
 				visible as bool32_t,
 			)
 		}
@@ -5267,7 +5651,7 @@ pub trait StereoKitMultiThread {
 		unsafe {
 			stereokit_sys::model_node_set_material(
 				model.as_ref().0.as_ptr(),
-				node,
+				node.0,
 				material.as_ref().0.as_ptr(),
 			)
 		}
@@ -5282,7 +5666,7 @@ pub trait StereoKitMultiThread {
 		unsafe {
 			stereokit_sys::model_node_set_mesh(
 				model.as_ref().0.as_ptr(),
-				node,
+				node.0,
 				mesh.as_ref().0.as_ptr(),
 			)
 		}
@@ -5297,7 +5681,7 @@ pub trait StereoKitMultiThread {
 		unsafe {
 			stereokit_sys::model_node_set_transform_model(
 				model.as_ref().0.as_ptr(),
-				node,
+				node.0,
 				transform_model_space.into().into(),
 			)
 		}
@@ -5312,7 +5696,7 @@ pub trait StereoKitMultiThread {
 		unsafe {
 			stereokit_sys::model_node_set_transform_local(
 				model.as_ref().0.as_ptr(),
-				node,
+				node.0,
 				transform_local_space.into().into(),
 			)
 		}
@@ -5328,7 +5712,7 @@ pub trait StereoKitMultiThread {
 		match NonNull::new (unsafe {
 			stereokit_sys::model_node_info_get(
 				model.as_ref().0.as_ptr(), 
-				node,
+				node.0,
 				info_key_utf8_c.as_ptr(),
 			) 
 		})  {
@@ -5350,7 +5734,7 @@ pub trait StereoKitMultiThread {
 		unsafe {
 			stereokit_sys::model_node_info_set(
 				model.as_ref().0.as_ptr(),
-				node,
+				node.0,
 				info_key_utf8.as_ptr(),
 				info_value_utf8.as_ptr(),
 			)
@@ -5367,34 +5751,36 @@ pub trait StereoKitMultiThread {
 		unsafe {
 			stereokit_sys::model_node_info_remove(
 				model.as_ref().0.as_ptr(),
-				node,
+				node.0,
 				info_key_utf8.as_ptr(),
 			) != 0
 		}
 	}
 
 	fn model_node_info_clear<M: AsRef<Model>>(&self, model: M, node: ModelNodeId) {
-		unsafe { stereokit_sys::model_node_info_clear(model.as_ref().0.as_ptr(), node) }
+		unsafe { stereokit_sys::model_node_info_clear(model.as_ref().0.as_ptr(), node.0) }
 	}
 
-	fn model_node_info_count<M: AsRef<Model>>(&self, model: M, node: ModelNodeId) -> ModelNodeId {
-		unsafe { stereokit_sys::model_node_info_count(model.as_ref().0.as_ptr(), node) }
+	/// How many key/value pairs of info this node has.
+	fn model_node_info_count<M: AsRef<Model>>(&self, model: M, node: ModelNodeId) -> i32 {
+		unsafe { stereokit_sys::model_node_info_count(model.as_ref().0.as_ptr(), node.0) }
 	}
 
+	/// Entries whose key or value isn't valid UTF-8 are skipped.
 	fn model_node_info_iterate<M: AsRef<Model>>(&self, model: M, mut iterator : i32,node: ModelNodeId) -> Option<(&str, &str, i32)> {
 
-		let out_key_utf8 = CString::new("H").unwrap().into_raw() as *mut *const std::os::raw::c_char;
-		let out_value_utf8 = CString::new("H").unwrap().into_raw() as *mut *const std::os::raw::c_char;
-
-		let ref_iterator  = &mut iterator as *mut i32;
+		let mut out_key_utf8: *const std::os::raw::c_char = null();
+		let mut out_value_utf8: *const std::os::raw::c_char = null();
 
 		unsafe {
-			let res = stereokit_sys::model_node_info_iterate(model.as_ref().0.as_ptr(), node, ref_iterator,  out_key_utf8,  out_value_utf8);
-			if res != 0 {
-				let key = CStr::from_ptr(*out_key_utf8);
-				let value = CStr::from_ptr(*out_value_utf8);
-				Some((key.to_str().unwrap(),value.to_str().unwrap(), *ref_iterator as i32))
-			} else {None}
+			while stereokit_sys::model_node_info_iterate(model.as_ref().0.as_ptr(), node.0, &mut iterator, &mut out_key_utf8, &mut out_value_utf8) != 0 {
+				let key = CStr::from_ptr(out_key_utf8).to_str();
+				let value = CStr::from_ptr(out_value_utf8).to_str();
+				if let (Ok(key), Ok(value)) = (key, value) {
+					return Some((key, value, iterator));
+				}
+			}
+			None
 		}
 	}
 
@@ -5777,7 +6163,16 @@ pub trait StereoKitMultiThread {
 		unsafe { *stereokit_sys::input_hand(hand as handed_) }.into()
 	}
 
-	//TODO: input_hand_override(hand: Handed, hand_joints: [])
+	/// This allows you to completely override the hand’s pose information! It is still treated like the user’s hand, so this is great for simulating input for testing purposes. It will remain overridden until you call input_hand_override_clear. Pass `&hand.fingers` to replay a recorded Hand.
+	fn input_hand_override(&self, hand: Handed, hand_joints: &[[HandJoint; 5]; 5]) {
+		let joints: [[hand_joint_t; 5]; 5] = hand_joints.map(|finger| finger.map(|joint| joint.into()));
+		unsafe { stereokit_sys::input_hand_override(hand as handed_, joints.as_ptr() as *const hand_joint_t) }
+	}
+
+	/// Gives the hand back to the user’s actual input after a call to input_hand_override.
+	fn input_hand_override_clear(&self, hand: Handed) {
+		unsafe { stereokit_sys::input_hand_override(hand as handed_, std::ptr::null()) }
+	}
 
 	fn input_controller(&self, hand: Handed) -> Controller {
 		unsafe { *stereokit_sys::input_controller(hand as handed_) }.into()
@@ -6088,6 +6483,29 @@ pub trait StereoKitMultiThread {
 			.unwrap()
 	}
 
+	/// Gets the asset at this index as a typed handle, or None if the index is out of range. The handle holds its own reference, so it’s safe to keep around, except for Tex and Solid handles which are borrowed, see [`AnyAsset`].
+	fn assets_get_any(&self, index: i32) -> Option<AnyAsset> {
+		let asset = self.assets_get_index(index)?;
+		unsafe { AnyAsset::from_raw(asset.0, self.assets_get_type(index)) }
+	}
+
+	/// An iterator over every asset StereoKit currently holds, great for debug tools and asset browsers!
+	fn assets_iter(&self) -> AssetIter<'_, Self>
+	where
+		Self: Sized,
+	{
+		AssetIter { sk: self, index: 0 }
+	}
+
+	/// Finds the first asset of any type with this id.
+	fn assets_find_any(&self, id: impl AsRef<str>) -> Option<AnyAsset>
+	where
+		Self: Sized,
+	{
+		let id = id.as_ref();
+		self.assets_iter().find(|asset| self.asset_get_id(asset) == id)
+	}
+
 	fn window<S: AsRef<str>>(
 		&self,
 		window_title: S,
@@ -6558,6 +6976,13 @@ fn sound_release(sound: &mut Sound) {
 	unsafe { stereokit_sys::sound_release(sound.0.as_ptr()) }
 }
 
+/// Tex doesn't release itself, so this is for the Drop of things that own
+/// their textures, where there's no handle to call `tex_release` on. Any other
+/// Tex pointing at the same texture must not be used after.
+pub(crate) unsafe fn tex_release(tex: &Tex) {
+	unsafe { stereokit_sys::tex_release(tex.0.as_ptr()) }
+}
+
 pub struct WindowContext(PhantomData<*const ()>);
 
 
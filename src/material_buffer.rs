@@ -0,0 +1,209 @@
+use crate::StereoKitMultiThread;
+use bytemuck::Pod;
+use glam::{IVec2, IVec3, IVec4, Mat4, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
+use std::ffi::c_void;
+use std::marker::PhantomData;
+
+/// A global shader constant buffer holding a `T`, so shader data like time,
+/// wind or light arrays can be pushed every frame without any unsafe code!
+/// Its size comes straight from `T`, so lay `T` out the way HLSL packs a
+/// cbuffer, [`material_buffer_struct!`](crate::material_buffer_struct) checks
+/// this at compile time.
+pub struct MaterialBuffer<T: Pod> {
+	buffer: crate::MaterialBuffer,
+	marker: PhantomData<T>,
+}
+
+impl<T: Pod> MaterialBuffer<T> {
+	/// Creates the buffer, and binds it to `register_slot` for every shader.
+	/// Slots 0 through 2 are StereoKit's own, so use 3 through 16. Panics if
+	/// `T` isn't a multiple of 16 bytes, which cbuffers always are.
+	pub fn new(sk: &impl StereoKitMultiThread, register_slot: i32) -> Self {
+		let size = std::mem::size_of::<T>();
+		assert!(
+			size > 0 && size % 16 == 0,
+			"material buffers must be a multiple of 16 bytes, {} is {size}",
+			std::any::type_name::<T>()
+		);
+		Self {
+			buffer: sk.material_buffer_create(register_slot, size as i32),
+			marker: PhantomData,
+		}
+	}
+
+	pub fn with_value(sk: &impl StereoKitMultiThread, register_slot: i32, value: &T) -> Self {
+		let buffer = Self::new(sk, register_slot);
+		buffer.set(sk, value);
+		buffer
+	}
+
+	/// Uploads a new value, shaders see it from the next draw on.
+	pub fn set(&self, sk: &impl StereoKitMultiThread, value: &T) {
+		// The buffer was created with size_of::<T>() bytes, so this reads exactly one T
+		unsafe { sk.material_buffer_set_data(&self.buffer, value as *const T as *const c_void) }
+	}
+
+	/// The untyped buffer underneath.
+	pub fn buffer(&self) -> &crate::MaterialBuffer {
+		&self.buffer
+	}
+}
+
+impl<T: Pod> AsRef<crate::MaterialBuffer> for MaterialBuffer<T> {
+	fn as_ref(&self) -> &crate::MaterialBuffer {
+		&self.buffer
+	}
+}
+
+/// A type that can be a field of a
+/// [`material_buffer_struct!`](crate::material_buffer_struct), because it's
+/// laid out the same in Rust as it is in an HLSL cbuffer.
+pub trait HlslField: Pod {}
+
+/// A type that fills whole 16 byte rows, so it can be the element of an array
+/// field. HLSL starts every array element on a new row, so arrays of anything
+/// smaller, like `[f32; 4]`, don't line up with what the shader reads.
+pub trait HlslRow: HlslField {}
+
+macro_rules! hlsl_fields {
+	($($ty:ty),*) => {
+		$(impl HlslField for $ty {})*
+	};
+}
+hlsl_fields!(f32, i32, u32, Vec2, Vec3, Vec4, IVec2, IVec3, IVec4, UVec2, UVec3, UVec4, Mat4);
+
+impl HlslRow for Vec4 {}
+impl HlslRow for IVec4 {}
+impl HlslRow for UVec4 {}
+impl HlslRow for Mat4 {}
+
+impl<T: HlslRow, const N: usize> HlslField for [T; N] where [T; N]: Pod {}
+
+/// Used by [`material_buffer_struct!`](crate::material_buffer_struct), this
+/// takes the struct's size and the (offset, size) of each field, and panics
+/// at compile time if HLSL would pack them differently.
+#[doc(hidden)]
+pub const fn check_hlsl_packing(size: usize, fields: &[(usize, usize)]) {
+	if size == 0 || size % 16 != 0 {
+		panic!("material buffer structs must be a multiple of 16 bytes, add padding fields");
+	}
+	let mut total = 0;
+	let mut i = 0;
+	while i < fields.len() {
+		let (offset, field_size) = fields[i];
+		total += field_size;
+		if field_size > 16 {
+			if offset % 16 != 0 || field_size % 16 != 0 {
+				panic!("fields bigger than 16 bytes, like matrices and arrays, must start on a 16 byte boundary and fill whole 16 byte rows");
+			}
+		} else if field_size > 0 && offset / 16 != (offset + field_size - 1) / 16 {
+			panic!("HLSL doesn't let a field cross a 16 byte boundary, move it or add padding before it");
+		}
+		i += 1;
+	}
+	if total != size {
+		panic!("material buffer struct has padding between fields, add explicit padding fields instead");
+	}
+}
+
+/// Declares a `#[repr(C)]` struct for use with [`MaterialBuffer`], that's
+/// `Copy`, `Clone` and `bytemuck::Pod`. It fails to compile unless the struct
+/// is packed the way HLSL packs a cbuffer: fields can't cross a 16 byte
+/// boundary, bigger fields start on one, there's no hidden padding, and the
+/// whole thing is a multiple of 16 bytes. Fields must be [`HlslField`]s, and
+/// since arrays in a cbuffer put each element on its own 16 byte row, arrays
+/// can only hold [`HlslRow`] types like `Vec4`. Structs declared this way can
+/// be fields of, and array elements in, other ones.
+///
+/// ```ignore
+/// stereokit::material_buffer_struct! {
+///     pub struct Wind {
+///         pub direction: glam::Vec3,
+///         pub strength: f32,
+///         pub time: f32,
+///         pub _pad: glam::Vec3,
+///     }
+/// }
+/// let wind = MaterialBuffer::<Wind>::new(&sk, 3);
+/// ```
+#[macro_export]
+macro_rules! material_buffer_struct {
+	(
+		$(#[$meta:meta])*
+		$vis:vis struct $name:ident {
+			$($(#[$field_meta:meta])* $field_vis:vis $field:ident : $ty:ty),* $(,)?
+		}
+	) => {
+		$(#[$meta])*
+		#[derive(Copy, Clone)]
+		#[repr(C)]
+		$vis struct $name {
+			$($(#[$field_meta])* $field_vis $field: $ty,)*
+		}
+
+		// Every field is Pod and there's no padding, the checks below make sure
+		unsafe impl $crate::bytemuck::Zeroable for $name {}
+		unsafe impl $crate::bytemuck::Pod for $name {}
+		impl $crate::material_buffer::HlslField for $name {}
+		// The packing check makes sure this is a multiple of 16 bytes
+		impl $crate::material_buffer::HlslRow for $name {}
+
+		const _: () = {
+			#[allow(dead_code)]
+			fn assert_fields_are_hlsl() {
+				fn assert_hlsl<T: $crate::material_buffer::HlslField>() {}
+				$(assert_hlsl::<$ty>();)*
+			}
+			$crate::material_buffer::check_hlsl_packing(
+				::core::mem::size_of::<$name>(),
+				&[$((::core::mem::offset_of!($name, $field), ::core::mem::size_of::<$ty>())),*],
+			);
+		};
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	crate::material_buffer_struct! {
+		#[allow(dead_code)]
+		struct Packed {
+			direction: Vec3,
+			strength: f32,
+			colors: [Vec4; 2],
+			transform: Mat4,
+		}
+	}
+
+	#[test]
+	fn accepts_hlsl_layouts() {
+		assert_eq!(std::mem::size_of::<Packed>(), 112);
+		check_hlsl_packing(32, &[(0, 12), (12, 4), (16, 8), (24, 8)]);
+		check_hlsl_packing(80, &[(0, 16), (16, 64)]);
+	}
+
+	#[test]
+	#[should_panic(expected = "16 byte boundary")]
+	fn rejects_crossing_fields() {
+		check_hlsl_packing(32, &[(0, 8), (8, 12), (20, 12)]);
+	}
+
+	#[test]
+	#[should_panic(expected = "whole 16 byte rows")]
+	fn rejects_unaligned_matrices() {
+		check_hlsl_packing(80, &[(0, 8), (8, 64), (72, 8)]);
+	}
+
+	#[test]
+	#[should_panic(expected = "multiple of 16 bytes")]
+	fn rejects_partial_rows() {
+		check_hlsl_packing(12, &[(0, 12)]);
+	}
+
+	#[test]
+	#[should_panic(expected = "explicit padding")]
+	fn rejects_hidden_padding() {
+		check_hlsl_packing(32, &[(0, 4), (16, 16)]);
+	}
+}
@@ -0,0 +1,326 @@
+use crate::{
+	Color128, CullMode, DepthTest, Material, MaterialParameter, Shader, SkResult, StereoKitError,
+	StereoKitMultiThread, Tex, Transparency,
+};
+use glam::{Mat4, Vec2, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where an asset comes from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum AssetSource {
+	/// A file path, relative to the assets folder.
+	File(PathBuf),
+	/// The id of an asset that already exists, like "default/material".
+	Id(String),
+}
+
+/// A value for a shader parameter.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum MaterialParam {
+	Float(f32),
+	Int(i32),
+	UInt(u32),
+	Bool(bool),
+	Vector2(Vec2),
+	Vector3(Vec3),
+	Vector4(Vec4),
+	Matrix(Mat4),
+	/// Linear color, rgba.
+	Color([f32; 4]),
+	/// A texture, loaded as sRGB when it comes from a file.
+	Texture(AssetSource),
+}
+
+/// Everything that makes up a Material, as plain serde data! Build one up
+/// fluently, or load a preset from JSON (or RON, with the `ron` feature), then
+/// turn it into a Material with [`MaterialDesc::create`]. Render state that's
+/// left as None keeps the shader's default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaterialDesc {
+	/// Sets the Material's asset id, when there is one.
+	#[serde(default)]
+	pub id: Option<String>,
+	pub shader: AssetSource,
+	#[serde(default)]
+	pub transparency: Option<Transparency>,
+	#[serde(default)]
+	pub cull: Option<CullMode>,
+	#[serde(default)]
+	pub wireframe: Option<bool>,
+	#[serde(default)]
+	pub depth_test: Option<DepthTest>,
+	#[serde(default)]
+	pub depth_write: Option<bool>,
+	#[serde(default)]
+	pub queue_offset: Option<i32>,
+	#[serde(default)]
+	pub params: BTreeMap<String, MaterialParam>,
+}
+
+impl MaterialDesc {
+	pub fn new(shader: AssetSource) -> Self {
+		Self {
+			id: None,
+			shader,
+			transparency: None,
+			cull: None,
+			wireframe: None,
+			depth_test: None,
+			depth_write: None,
+			queue_offset: None,
+			params: BTreeMap::new(),
+		}
+	}
+
+	/// Uses a shader that's already loaded, like "default/shader_pbr".
+	pub fn with_shader_id(id: impl Into<String>) -> Self {
+		Self::new(AssetSource::Id(id.into()))
+	}
+
+	/// Uses a compiled .sks shader file, relative to the assets folder.
+	pub fn with_shader_file(path: impl Into<PathBuf>) -> Self {
+		Self::new(AssetSource::File(path.into()))
+	}
+
+	pub fn id(mut self, id: impl Into<String>) -> Self {
+		self.id = Some(id.into());
+		self
+	}
+	pub fn transparency(mut self, transparency: Transparency) -> Self {
+		self.transparency = Some(transparency);
+		self
+	}
+	pub fn cull(mut self, cull: CullMode) -> Self {
+		self.cull = Some(cull);
+		self
+	}
+	pub fn wireframe(mut self, wireframe: bool) -> Self {
+		self.wireframe = Some(wireframe);
+		self
+	}
+	pub fn depth_test(mut self, depth_test: DepthTest) -> Self {
+		self.depth_test = Some(depth_test);
+		self
+	}
+	pub fn depth_write(mut self, depth_write: bool) -> Self {
+		self.depth_write = Some(depth_write);
+		self
+	}
+	pub fn queue_offset(mut self, queue_offset: i32) -> Self {
+		self.queue_offset = Some(queue_offset);
+		self
+	}
+
+	pub fn param(mut self, name: impl Into<String>, value: MaterialParam) -> Self {
+		self.params.insert(name.into(), value);
+		self
+	}
+	pub fn float(self, name: impl Into<String>, value: f32) -> Self {
+		self.param(name, MaterialParam::Float(value))
+	}
+	/// A linear space color.
+	pub fn color(self, name: impl Into<String>, color: Color128) -> Self {
+		self.param(
+			name,
+			MaterialParam::Color([color.r, color.g, color.b, color.a]),
+		)
+	}
+	/// An image file relative to the assets folder, loaded as sRGB.
+	pub fn texture_file(self, name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+		self.param(name, MaterialParam::Texture(AssetSource::File(path.into())))
+	}
+	/// A texture that's already loaded, like "default/tex_black".
+	pub fn texture_id(self, name: impl Into<String>, id: impl Into<String>) -> Self {
+		self.param(name, MaterialParam::Texture(AssetSource::Id(id.into())))
+	}
+
+	/// Creates a new Material from this description, loading the shader and
+	/// any textures it needs.
+	pub fn create(&self, sk: &impl StereoKitMultiThread) -> SkResult<Material> {
+		let shader = load_shader(sk, &self.shader)?;
+		let material = sk.material_create(&shader);
+		if let Some(id) = &self.id {
+			sk.material_set_id(&material, id.clone());
+		}
+		self.apply(sk, &material)?;
+		Ok(material)
+	}
+
+	/// Sets this description's render state and parameters on an existing
+	/// Material, leaving its shader alone.
+	pub fn apply(&self, sk: &impl StereoKitMultiThread, material: &Material) -> SkResult<()> {
+		if let Some(transparency) = self.transparency {
+			sk.material_set_transparency(material, transparency);
+		}
+		if let Some(cull) = self.cull {
+			sk.material_set_cull(material, cull);
+		}
+		if let Some(wireframe) = self.wireframe {
+			sk.material_set_wireframe(material, wireframe);
+		}
+		if let Some(depth_test) = self.depth_test {
+			sk.material_set_depth_test(material, depth_test);
+		}
+		if let Some(depth_write) = self.depth_write {
+			sk.material_set_depth_write(material, depth_write);
+		}
+		if let Some(queue_offset) = self.queue_offset {
+			sk.material_set_queue_offset(material, queue_offset);
+		}
+		for (name, param) in &self.params {
+			apply_param(sk, material, name, param)?;
+		}
+		Ok(())
+	}
+
+	/// Reads everything back out of a Material, for saving it as a preset.
+	/// The id is left out, so creating the preset doesn't clash with the
+	/// Material it came from, and unset texture slots are skipped.
+	/// Textures with a "default/" id are saved as ids, and ones whose id names
+	/// a file that exists are saved as files, since StereoKit names textures
+	/// loaded from files after their path. Other textures, like generated ones,
+	/// can't be recreated from their id, so they're left out.
+	/// Integer vector parameters can't be read back yet, so they're left out.
+	pub fn from_material(sk: &impl StereoKitMultiThread, material: &Material) -> Self {
+		let shader = sk.material_get_shader(material);
+		let mut desc = Self::with_shader_id(sk.shader_get_id(&shader))
+			.transparency(sk.material_get_transparency(material))
+			.cull(sk.material_get_cull(material))
+			.wireframe(sk.material_get_wireframe(material))
+			.depth_test(sk.material_get_depth_test(material))
+			.depth_write(sk.material_get_depth_write(material))
+			.queue_offset(sk.material_get_queue_offset(material));
+		let assets_folder = sk.get_settings().assets_folder;
+
+		for index in 0..sk.material_get_param_count(material) {
+			let Some((name, kind)) = sk.material_get_param_info(material, index) else {
+				continue;
+			};
+			let value = match kind {
+				MaterialParameter::Float => {
+					MaterialParam::Float(sk.material_get_float(material, &name))
+				}
+				MaterialParameter::Color128 => {
+					let color = sk.material_get_color(material, &name);
+					MaterialParam::Color([color.r, color.g, color.b, color.a])
+				}
+				MaterialParameter::Vec2 => {
+					MaterialParam::Vector2(sk.material_get_vector2(material, &name))
+				}
+				MaterialParameter::Vec3 => {
+					MaterialParam::Vector3(sk.material_get_vector3(material, &name))
+				}
+				MaterialParameter::Vec4 => {
+					MaterialParam::Vector4(sk.material_get_vector4(material, &name))
+				}
+				MaterialParameter::Matrix => {
+					MaterialParam::Matrix(sk.material_get_matrix(material, &name))
+				}
+				MaterialParameter::Int => MaterialParam::Int(sk.material_get_int(material, &name)),
+				MaterialParameter::UInt => {
+					MaterialParam::UInt(sk.material_get_uint(material, &name))
+				}
+				MaterialParameter::Texture => {
+					let Some(tex) = sk.material_try_get_texture(material, &name) else {
+						continue;
+					};
+					let id = sk.tex_get_id(&tex).to_string();
+					let path = PathBuf::from(&id);
+					if id.starts_with("default/") {
+						MaterialParam::Texture(AssetSource::Id(id))
+					} else if path.is_file() || assets_folder.join(&path).is_file() {
+						MaterialParam::Texture(AssetSource::File(path))
+					} else {
+						continue;
+					}
+				}
+				_ => continue,
+			};
+			desc.params.insert(name, value);
+		}
+		desc
+	}
+
+	pub fn from_json(json: &str) -> SkResult<Self> {
+		serde_json::from_str(json).map_err(|e| StereoKitError::MaterialParse(e.to_string()))
+	}
+	pub fn to_json(&self) -> SkResult<String> {
+		serde_json::to_string_pretty(self).map_err(|e| StereoKitError::MaterialParse(e.to_string()))
+	}
+	#[cfg(feature = "ron")]
+	pub fn from_ron(ron: &str) -> SkResult<Self> {
+		ron::from_str(ron).map_err(|e| StereoKitError::MaterialParse(e.to_string()))
+	}
+	#[cfg(feature = "ron")]
+	pub fn to_ron(&self) -> SkResult<String> {
+		ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+			.map_err(|e| StereoKitError::MaterialParse(e.to_string()))
+	}
+
+	/// Reads a material preset, files ending in .ron are read as RON, everything else as JSON.
+	pub fn load_file(path: impl AsRef<Path>) -> SkResult<Self> {
+		let path = path.as_ref();
+		let text = std::fs::read_to_string(path)
+			.map_err(|e| StereoKitError::MaterialFile(path.to_path_buf(), e.to_string()))?;
+		match path.extension().and_then(|ext| ext.to_str()) {
+			#[cfg(feature = "ron")]
+			Some("ron") => Self::from_ron(&text),
+			_ => Self::from_json(&text),
+		}
+	}
+
+	/// Writes a material preset, picking the format the same way as [`MaterialDesc::load_file`].
+	pub fn save_file(&self, path: impl AsRef<Path>) -> SkResult<()> {
+		let path = path.as_ref();
+		let text = match path.extension().and_then(|ext| ext.to_str()) {
+			#[cfg(feature = "ron")]
+			Some("ron") => self.to_ron()?,
+			_ => self.to_json()?,
+		};
+		std::fs::write(path, text)
+			.map_err(|e| StereoKitError::MaterialFile(path.to_path_buf(), e.to_string()))
+	}
+}
+
+/// Sets a single shader parameter, loading its texture if it has one.
+pub fn apply_param(
+	sk: &impl StereoKitMultiThread,
+	material: &Material,
+	name: &str,
+	param: &MaterialParam,
+) -> SkResult<()> {
+	match param {
+		MaterialParam::Float(value) => sk.material_set_float(material, name, *value),
+		MaterialParam::Int(value) => sk.material_set_int(material, name, *value),
+		MaterialParam::UInt(value) => sk.material_set_uint(material, name, *value),
+		MaterialParam::Bool(value) => sk.material_set_bool(material, name, *value),
+		MaterialParam::Vector2(value) => sk.material_set_vector2(material, name, *value),
+		MaterialParam::Vector3(value) => sk.material_set_vector3(material, name, *value),
+		MaterialParam::Vector4(value) => sk.material_set_vector4(material, name, *value),
+		MaterialParam::Matrix(value) => sk.material_set_matrix(material, name, *value),
+		MaterialParam::Color([r, g, b, a]) => {
+			sk.material_set_color(material, name, Color128::new(*r, *g, *b, *a))
+		}
+		MaterialParam::Texture(source) => {
+			let tex = load_tex(sk, source)?;
+			sk.material_set_texture(material, name, tex);
+		}
+	}
+	Ok(())
+}
+
+pub(crate) fn load_tex(sk: &impl StereoKitMultiThread, source: &AssetSource) -> SkResult<Tex> {
+	match source {
+		AssetSource::File(path) => sk.tex_create_file(path, true, 0),
+		AssetSource::Id(id) => sk.tex_find(id.clone()),
+	}
+}
+
+fn load_shader(sk: &impl StereoKitMultiThread, source: &AssetSource) -> SkResult<Shader> {
+	match source {
+		AssetSource::File(path) => sk.shader_create_file(path),
+		AssetSource::Id(id) => sk.shader_find(id.clone()),
+	}
+}
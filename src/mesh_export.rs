@@ -0,0 +1,329 @@
+use crate::{Mesh, SkResult, StereoKitError, StereoKitMultiThread, Vert};
+use glam::Vec3;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// The flavor of STL file to write.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StlFormat {
+	/// The compact, and much more common, binary format.
+	Binary,
+	/// Human readable text, this gets big quickly!
+	Ascii,
+}
+
+/// The flavor of PLY file to write.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PlyFormat {
+	/// Binary little endian, the compact option.
+	Binary,
+	/// Human readable text, this is also the flavor StereoKit can load.
+	Ascii,
+}
+
+/// Copies out a Mesh's CPU side data, with the triangles re-wound counter-clockwise
+/// since StereoKit winds clockwise, and every format here expects the opposite.
+fn mesh_data(sk: &impl StereoKitMultiThread, mesh: &Mesh) -> SkResult<(Vec<Vert>, Vec<u32>)> {
+	let verts = sk.mesh_get_verts_ref(mesh).to_vec();
+	let inds = rewind(sk.mesh_get_inds_ref(mesh));
+	if verts.is_empty() || inds.is_empty() {
+		return Err(StereoKitError::MeshExport(
+			sk.mesh_get_id(mesh).to_string(),
+			"the mesh has no CPU side data, it needs mesh_set_keep_data".to_string(),
+		));
+	}
+	Ok((verts, inds))
+}
+
+/// Swaps the winding of each triangle.
+fn rewind(inds: &[i32]) -> Vec<u32> {
+	inds.chunks_exact(3)
+		.flat_map(|tri| [tri[0], tri[2], tri[1]])
+		.map(|i| i as u32)
+		.collect()
+}
+
+fn write_file(file: &Path, data: impl AsRef<[u8]>) -> SkResult<()> {
+	std::fs::write(file, data)
+		.map_err(|e| StereoKitError::ExportFile(file.to_path_buf(), e.to_string()))
+}
+
+/// Writes a Mesh as a Wavefront .obj file, with positions, normals and UVs.
+/// Meshes need to be keeping their data on the CPU to be exported, which is
+/// the case for meshes from `mesh_gen_*`, `mesh_set_data` or loaded models.
+pub fn mesh_to_obj(sk: &impl StereoKitMultiThread, mesh: impl AsRef<Mesh>) -> SkResult<String> {
+	let mesh = mesh.as_ref();
+	let (verts, inds) = mesh_data(sk, mesh)?;
+	Ok(obj_from_data(sk.mesh_get_id(mesh), &verts, &inds))
+}
+
+/// The text of an OBJ file, from counter-clockwise triangles.
+fn obj_from_data(name: &str, verts: &[Vert], inds: &[u32]) -> String {
+	let mut obj = String::new();
+	writeln!(obj, "# exported by stereokit-rs").unwrap();
+	writeln!(obj, "o {name}").unwrap();
+	for vert in verts {
+		writeln!(obj, "v {} {} {}", vert.pos.x, vert.pos.y, vert.pos.z).unwrap();
+	}
+	for vert in verts {
+		writeln!(obj, "vn {} {} {}", vert.norm.x, vert.norm.y, vert.norm.z).unwrap();
+	}
+	for vert in verts {
+		// OBJ has V going up, where StereoKit's goes down
+		writeln!(obj, "vt {} {}", vert.uv.x, 1.0 - vert.uv.y).unwrap();
+	}
+	for tri in inds.chunks_exact(3) {
+		let [a, b, c] = [tri[0] + 1, tri[1] + 1, tri[2] + 1];
+		writeln!(obj, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}").unwrap();
+	}
+	obj
+}
+
+/// Writes a Mesh to a .obj file, see [`mesh_to_obj`].
+pub fn mesh_save_obj(
+	sk: &impl StereoKitMultiThread,
+	mesh: impl AsRef<Mesh>,
+	file: impl AsRef<Path>,
+) -> SkResult<()> {
+	write_file(file.as_ref(), mesh_to_obj(sk, mesh)?)
+}
+
+fn face_normal(verts: &[Vert], tri: &[u32]) -> Vec3 {
+	let a = verts[tri[0] as usize].pos;
+	let b = verts[tri[1] as usize].pos;
+	let c = verts[tri[2] as usize].pos;
+	(b - a).cross(c - a).normalize_or_zero()
+}
+
+/// Writes a Mesh as an STL file. STL only knows about triangles, so normals
+/// are per face, and UVs and colors are dropped.
+pub fn mesh_to_stl(
+	sk: &impl StereoKitMultiThread,
+	mesh: impl AsRef<Mesh>,
+	format: StlFormat,
+) -> SkResult<Vec<u8>> {
+	let mesh = mesh.as_ref();
+	let (verts, inds) = mesh_data(sk, mesh)?;
+	Ok(stl_from_data(sk.mesh_get_id(mesh), &verts, &inds, format))
+}
+
+/// The bytes of an STL file, from counter-clockwise triangles.
+fn stl_from_data(name: &str, verts: &[Vert], inds: &[u32], format: StlFormat) -> Vec<u8> {
+	let tris = inds.chunks_exact(3);
+	match format {
+		StlFormat::Binary => {
+			let mut stl = Vec::with_capacity(84 + tris.len() * 50);
+			let mut header = [0u8; 80];
+			let name = b"exported by stereokit-rs";
+			header[..name.len()].copy_from_slice(name);
+			stl.extend(header);
+			stl.extend((tris.len() as u32).to_le_bytes());
+			for tri in tris {
+				let normal = face_normal(verts, tri);
+				let points = [tri[0], tri[1], tri[2]].map(|i| verts[i as usize].pos);
+				for v in [normal].iter().chain(points.iter()) {
+					v.to_array()
+						.iter()
+						.for_each(|f| stl.extend(f.to_le_bytes()));
+				}
+				stl.extend(0u16.to_le_bytes());
+			}
+			stl
+		}
+		StlFormat::Ascii => {
+			let name = name.replace(char::is_whitespace, "_");
+			let mut stl = String::new();
+			writeln!(stl, "solid {name}").unwrap();
+			for tri in tris {
+				let normal = face_normal(verts, tri);
+				writeln!(stl, "facet normal {} {} {}", normal.x, normal.y, normal.z).unwrap();
+				writeln!(stl, "  outer loop").unwrap();
+				for &i in tri {
+					let pos = verts[i as usize].pos;
+					writeln!(stl, "    vertex {} {} {}", pos.x, pos.y, pos.z).unwrap();
+				}
+				writeln!(stl, "  endloop").unwrap();
+				writeln!(stl, "endfacet").unwrap();
+			}
+			writeln!(stl, "endsolid {name}").unwrap();
+			stl.into_bytes()
+		}
+	}
+}
+
+/// Writes a Mesh to a .stl file, see [`mesh_to_stl`].
+pub fn mesh_save_stl(
+	sk: &impl StereoKitMultiThread,
+	mesh: impl AsRef<Mesh>,
+	format: StlFormat,
+	file: impl AsRef<Path>,
+) -> SkResult<()> {
+	write_file(file.as_ref(), mesh_to_stl(sk, mesh, format)?)
+}
+
+/// Writes a Mesh as a PLY file, with positions, normals, UVs and vertex
+/// colors from `Vert::col`. The property names match what StereoKit's own PLY
+/// loader reads, so ASCII files round-trip through `mesh_find`/`model_create_file`.
+pub fn mesh_to_ply(
+	sk: &impl StereoKitMultiThread,
+	mesh: impl AsRef<Mesh>,
+	format: PlyFormat,
+) -> SkResult<Vec<u8>> {
+	let (verts, inds) = mesh_data(sk, mesh.as_ref())?;
+	Ok(ply_from_data(&verts, &inds, format))
+}
+
+/// The bytes of a PLY file, from counter-clockwise triangles.
+fn ply_from_data(verts: &[Vert], inds: &[u32], format: PlyFormat) -> Vec<u8> {
+	let mut header = String::new();
+	writeln!(header, "ply").unwrap();
+	match format {
+		PlyFormat::Binary => writeln!(header, "format binary_little_endian 1.0").unwrap(),
+		PlyFormat::Ascii => writeln!(header, "format ascii 1.0").unwrap(),
+	}
+	writeln!(header, "comment exported by stereokit-rs").unwrap();
+	writeln!(header, "element vertex {}", verts.len()).unwrap();
+	for property in ["x", "y", "z", "nx", "ny", "nz", "s", "t"] {
+		writeln!(header, "property float {property}").unwrap();
+	}
+	for property in ["red", "green", "blue", "alpha"] {
+		writeln!(header, "property uchar {property}").unwrap();
+	}
+	writeln!(header, "element face {}", inds.len() / 3).unwrap();
+	writeln!(header, "property list uchar uint vertex_indices").unwrap();
+	writeln!(header, "end_header").unwrap();
+
+	match format {
+		PlyFormat::Binary => {
+			let mut ply = header.into_bytes();
+			for vert in verts {
+				let floats = [
+					vert.pos.x,
+					vert.pos.y,
+					vert.pos.z,
+					vert.norm.x,
+					vert.norm.y,
+					vert.norm.z,
+					vert.uv.x,
+					vert.uv.y,
+				];
+				floats.iter().for_each(|f| ply.extend(f.to_le_bytes()));
+				ply.extend([vert.col.r, vert.col.g, vert.col.b, vert.col.a]);
+			}
+			for tri in inds.chunks_exact(3) {
+				ply.push(3);
+				tri.iter().for_each(|i| ply.extend(i.to_le_bytes()));
+			}
+			ply
+		}
+		PlyFormat::Ascii => {
+			let mut ply = header;
+			for vert in verts {
+				writeln!(
+					ply,
+					"{} {} {} {} {} {} {} {} {} {} {} {}",
+					vert.pos.x,
+					vert.pos.y,
+					vert.pos.z,
+					vert.norm.x,
+					vert.norm.y,
+					vert.norm.z,
+					vert.uv.x,
+					vert.uv.y,
+					vert.col.r,
+					vert.col.g,
+					vert.col.b,
+					vert.col.a
+				)
+				.unwrap();
+			}
+			for tri in inds.chunks_exact(3) {
+				writeln!(ply, "3 {} {} {}", tri[0], tri[1], tri[2]).unwrap();
+			}
+			ply.into_bytes()
+		}
+	}
+}
+
+/// Writes a Mesh to a .ply file, see [`mesh_to_ply`].
+pub fn mesh_save_ply(
+	sk: &impl StereoKitMultiThread,
+	mesh: impl AsRef<Mesh>,
+	format: PlyFormat,
+	file: impl AsRef<Path>,
+) -> SkResult<()> {
+	write_file(file.as_ref(), mesh_to_ply(sk, mesh, format)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Color32;
+
+	/// A single triangle, re-wound from the clockwise order StereoKit stores.
+	fn triangle() -> (Vec<Vert>, Vec<u32>) {
+		let white = Color32 {
+			r: 255,
+			g: 255,
+			b: 255,
+			a: 255,
+		};
+		let verts = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]].map(|[x, y]| Vert {
+			pos: Vec3::new(x, y, 0.0),
+			norm: Vec3::Z,
+			uv: glam::Vec2::new(x, y),
+			col: white,
+		});
+		(verts.to_vec(), rewind(&[0, 1, 2]))
+	}
+
+	#[test]
+	fn rewinds_whole_triangles() {
+		assert_eq!(rewind(&[0, 1, 2, 3, 4, 5, 6]), [0, 2, 1, 3, 5, 4]);
+	}
+
+	#[test]
+	fn obj_encoding() {
+		let (verts, inds) = triangle();
+		let obj = obj_from_data("tri", &verts, &inds);
+		assert!(obj.contains("o tri\n"));
+		assert_eq!(obj.lines().filter(|line| line.starts_with("v ")).count(), 3);
+		assert!(obj.contains("f 1/1/1 3/3/3 2/2/2\n"));
+	}
+
+	#[test]
+	fn stl_encoding() {
+		let (verts, inds) = triangle();
+		let stl = stl_from_data("tri", &verts, &inds, StlFormat::Binary);
+		assert_eq!(stl.len(), 84 + 50);
+		assert_eq!(u32::from_le_bytes(stl[80..84].try_into().unwrap()), 1);
+		let normal: Vec<f32> = stl[84..96]
+			.chunks_exact(4)
+			.map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+			.collect();
+		// Clockwise seen from the front, so StereoKit shows this towards -Z
+		assert_eq!(normal, [0.0, 0.0, -1.0]);
+
+		let stl =
+			String::from_utf8(stl_from_data("a tri", &verts, &inds, StlFormat::Ascii)).unwrap();
+		assert!(stl.starts_with("solid a_tri\n"));
+		assert!(stl.contains("facet normal 0 0 -1\n"));
+	}
+
+	#[test]
+	fn ply_encoding() {
+		let (verts, inds) = triangle();
+		let ply = String::from_utf8(ply_from_data(&verts, &inds, PlyFormat::Ascii)).unwrap();
+		assert!(ply.contains("element vertex 3\n"));
+		assert!(ply.contains("element face 1\n"));
+		assert!(ply.ends_with("3 0 2 1\n"));
+
+		let ply = ply_from_data(&verts, &inds, PlyFormat::Binary);
+		let header_end = b"end_header\n";
+		let header = ply
+			.windows(header_end.len())
+			.position(|window| window == header_end)
+			.unwrap() + header_end.len();
+		assert_eq!(ply.len() - header, 3 * (8 * 4 + 4) + (1 + 3 * 4));
+	}
+}
@@ -0,0 +1,263 @@
+use crate::camera::{Camera, CameraProjection};
+use crate::named_colors::{BLACK, WHITE};
+use crate::{
+	Color32, CullMode, Material, Mesh, Pose, RenderClear, RenderLayer, StereoKitDraw,
+	StereoKitMultiThread, Tex, TextureFormat, TextureType, Vert,
+};
+use glam::{Mat3, Mat4, Quat, Vec2, Vec3};
+
+/// The quad, render target and material shared by mirrors and portals.
+struct Surface {
+	size: Vec2,
+	resolution: (i32, i32),
+	target: Tex,
+	material: Material,
+	mesh: Mesh,
+	far: f32,
+}
+
+impl Surface {
+	fn new(
+		sk: &impl StereoKitMultiThread,
+		size: Vec2,
+		resolution: (i32, i32),
+		flip_u: bool,
+	) -> Self {
+		let target = sk.tex_gen_color(
+			BLACK,
+			resolution.0,
+			resolution.1,
+			TextureType::RENDER_TARGET,
+			TextureFormat::RGBA32,
+		);
+		sk.tex_add_zbuffer(&target, TextureFormat::Depth32);
+
+		let material = sk.material_copy_id("default/material_unlit");
+		sk.material_set_texture(&material, "diffuse", &target);
+		// Seen from the back, it'll just look like the back of the image
+		sk.material_set_cull(&material, CullMode::None);
+
+		// Looking at the front of the quad, which faces Forward, local +X is on
+		// the left, so that's where the left of the image goes
+		let (left, right) = if flip_u { (1.0, 0.0) } else { (0.0, 1.0) };
+		let corner = |x: f32, y: f32, u: f32, v: f32| Vert {
+			pos: Vec3::new(x, y, 0.0),
+			norm: Vec3::NEG_Z,
+			uv: Vec2::new(u, v),
+			col: Color32 {
+				r: 255,
+				g: 255,
+				b: 255,
+				a: 255,
+			},
+		};
+		let verts = [
+			corner(0.5, 0.5, left, 0.0),
+			corner(-0.5, 0.5, right, 0.0),
+			corner(-0.5, -0.5, right, 1.0),
+			corner(0.5, -0.5, left, 1.0),
+		];
+		let mesh = sk.mesh_create();
+		sk.mesh_set_data(&mesh, &verts, &[0, 1, 2, 0, 2, 3], true);
+
+		Self {
+			size,
+			resolution,
+			target,
+			material,
+			mesh,
+			far: 50.0,
+		}
+	}
+
+	/// Builds a camera at `position`, looking straight through the surface at
+	/// `pose` along `forward`, with a frustum that exactly fits the quad. Returns
+	/// None if the camera is behind the surface.
+	fn camera_through(&self, pose: Pose, position: Vec3, forward: Vec3) -> Option<Camera> {
+		let up = pose.orientation * Vec3::Y;
+		let distance = (pose.position - position).dot(forward);
+		if distance <= 0.001 {
+			return None;
+		}
+		let orientation = Quat::from_mat3(&Mat3::from_cols(forward.cross(up), up, -forward));
+		let camera_pose = Pose::new(position, orientation);
+
+		let view = Mat4::from_rotation_translation(orientation, position).inverse();
+		let center = view.transform_point3(pose.position);
+		let half_x = self.size.x * 0.5;
+		let half_y = self.size.y * 0.5;
+		// Camera and surface share an up vector, and the camera's right is
+		// either the surface's +X or -X, so the quad is axis aligned in view space
+		let angle = |offset: f32| (offset / distance).atan().to_degrees();
+		Some(Camera {
+			pose: camera_pose,
+			projection: CameraProjection::Fov {
+				left: angle(center.x - half_x),
+				right: angle(center.x + half_x),
+				top: angle(center.y + half_y),
+				bottom: angle(center.y - half_y),
+			},
+			near: distance,
+			far: distance + self.far,
+			viewport: crate::Rect {
+				x: 0.0,
+				y: 0.0,
+				w: 0.0,
+				h: 0.0,
+			},
+		})
+	}
+
+	fn draw(&self, sk: &impl StereoKitDraw, pose: Pose, layer: RenderLayer) {
+		let transform = Mat4::from_scale_rotation_translation(
+			self.size.extend(1.0),
+			pose.orientation,
+			pose.position,
+		);
+		sk.mesh_draw(&self.mesh, &self.material, transform, WHITE, layer);
+	}
+}
+
+impl Drop for Surface {
+	fn drop(&mut self) {
+		// The material keeps its own reference for as long as it needs one
+		unsafe { crate::tex_release(&self.target) };
+	}
+}
+
+/// A rectangular mirror! Each frame it renders the scene reflected across its
+/// surface into its own texture, and draws itself as a quad with that texture.
+/// The mirror faces its pose's Forward direction, and is `size` meters wide and
+/// tall.
+pub struct Mirror {
+	pub pose: Pose,
+	/// The layer the mirror quad itself draws on, this is never rendered into
+	/// the reflection so the mirror doesn't see itself.
+	pub layer: RenderLayer,
+	/// Layers that shouldn't show up in the reflection, like first-person hands.
+	pub exclude: RenderLayer,
+	surface: Surface,
+}
+
+impl Mirror {
+	/// Creates a mirror with a render target of `resolution` pixels, this should
+	/// roughly match the mirror's aspect ratio.
+	pub fn new(
+		sk: &impl StereoKitMultiThread,
+		pose: impl Into<Pose>,
+		size: impl Into<Vec2>,
+		resolution: (i32, i32),
+	) -> Self {
+		Self {
+			pose: pose.into(),
+			layer: RenderLayer::LAYER9,
+			exclude: RenderLayer::empty(),
+			surface: Surface::new(sk, size.into(), resolution, true),
+		}
+	}
+
+	pub fn size(&self) -> Vec2 {
+		self.surface.size
+	}
+	pub fn set_size(&mut self, size: impl Into<Vec2>) {
+		self.surface.size = size.into();
+	}
+	pub fn resolution(&self) -> (i32, i32) {
+		self.surface.resolution
+	}
+	/// The texture the reflection is rendered into.
+	pub fn texture(&self) -> &Tex {
+		&self.surface.target
+	}
+	/// How far the reflection renders, in meters past the mirror's surface.
+	pub fn set_far(&mut self, far: f32) {
+		self.surface.far = far;
+	}
+
+	/// The camera that sees what a viewer at `viewer` sees in the mirror, or
+	/// None if the viewer is behind it.
+	pub fn camera_for(&self, viewer: Vec3) -> Option<Camera> {
+		let normal = self.pose.forward();
+		let reflected = viewer - 2.0 * (viewer - self.pose.position).dot(normal) * normal;
+		self.surface.camera_through(self.pose, reflected, normal)
+	}
+
+	/// Renders the reflection as seen from the user's head, and draws the mirror.
+	pub fn draw(&self, sk: &impl StereoKitDraw) {
+		if let Some(camera) = self.camera_for(sk.input_head().position) {
+			let filter = RenderLayer::LAYER_ALL_REGULAR - self.exclude - self.layer;
+			camera.render_to(sk, &self.surface.target, filter, RenderClear::All);
+		}
+		self.surface.draw(sk, self.pose, self.layer);
+	}
+}
+
+/// A window from one place to another! Looking into the portal at `pose`
+/// shows what's behind `exit`, as if the viewer were standing in front of
+/// `exit` instead. Both face their pose's Forward direction. This also works
+/// as a security camera screen, with an `exit` that never moves.
+pub struct Portal {
+	pub pose: Pose,
+	pub exit: Pose,
+	/// The layer the portal quad itself draws on, this is never rendered into
+	/// the portal's view.
+	pub layer: RenderLayer,
+	/// Layers that shouldn't show up through the portal, like first-person hands.
+	pub exclude: RenderLayer,
+	surface: Surface,
+}
+
+impl Portal {
+	pub fn new(
+		sk: &impl StereoKitMultiThread,
+		pose: impl Into<Pose>,
+		exit: impl Into<Pose>,
+		size: impl Into<Vec2>,
+		resolution: (i32, i32),
+	) -> Self {
+		Self {
+			pose: pose.into(),
+			exit: exit.into(),
+			layer: RenderLayer::LAYER9,
+			exclude: RenderLayer::empty(),
+			surface: Surface::new(sk, size.into(), resolution, false),
+		}
+	}
+
+	pub fn size(&self) -> Vec2 {
+		self.surface.size
+	}
+	pub fn set_size(&mut self, size: impl Into<Vec2>) {
+		self.surface.size = size.into();
+	}
+	pub fn resolution(&self) -> (i32, i32) {
+		self.surface.resolution
+	}
+	/// The texture the view through the portal is rendered into.
+	pub fn texture(&self) -> &Tex {
+		&self.surface.target
+	}
+	/// How far the view through the portal renders, in meters past the exit.
+	pub fn set_far(&mut self, far: f32) {
+		self.surface.far = far;
+	}
+
+	/// The camera that sees what a viewer at `viewer` sees through the portal,
+	/// or None if the viewer is behind it.
+	pub fn camera_for(&self, viewer: Vec3) -> Option<Camera> {
+		let entrance = Mat4::from_rotation_translation(self.pose.orientation, self.pose.position);
+		let exit = Mat4::from_rotation_translation(self.exit.orientation, self.exit.position);
+		let position = (exit * entrance.inverse()).transform_point3(viewer);
+		self.surface
+			.camera_through(self.exit, position, -self.exit.forward())
+	}
+
+	/// Renders the view through the portal as seen from the user's head, and draws the portal.
+	pub fn draw(&self, sk: &impl StereoKitDraw) {
+		if let Some(camera) = self.camera_for(sk.input_head().position) {
+			let filter = RenderLayer::LAYER_ALL_REGULAR - self.exclude - self.layer;
+			camera.render_to(sk, &self.surface.target, filter, RenderClear::All);
+		}
+		self.surface.draw(sk, self.pose, self.layer);
+	}
+}
@@ -0,0 +1,359 @@
+use crate::{Material, Mesh, Model, ModelNodeId, SkResult, StereoKitError, StereoKitMultiThread};
+use glam::Mat4;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// A node in a Model's hierarchy, borrowing the Model it came from! This
+/// keeps a [`ModelNodeId`] paired with its Model, so ids can't accidentally be
+/// used with a different Model, and makes walking the tree much less wordy
+/// than the `model_node_*` functions.
+#[derive(Copy, Clone)]
+pub struct ModelNode<'a> {
+	model: &'a Model,
+	id: ModelNodeId,
+}
+
+impl<'a> ModelNode<'a> {
+	/// Pairs up a node id with the Model it belongs to.
+	pub(crate) fn new(model: &'a Model, id: ModelNodeId) -> Self {
+		Self { model, id }
+	}
+
+	/// The first root node of the Model, or None if it has no nodes.
+	pub fn root(sk: &impl StereoKitMultiThread, model: &'a Model) -> Option<Self> {
+		Some(Self::new(model, sk.model_node_get_root(model)?))
+	}
+
+	/// Finds the first node anywhere in the Model with this name.
+	pub fn find(
+		sk: &impl StereoKitMultiThread,
+		model: &'a Model,
+		name: impl AsRef<str>,
+	) -> Option<Self> {
+		Some(Self::new(model, sk.model_node_find(model, name)?))
+	}
+
+	/// Finds a node by the names along its path from the roots, separated by
+	/// `/`, like `"Armature/Hand.L"`.
+	pub fn find_path(
+		sk: &impl StereoKitMultiThread,
+		model: &'a Model,
+		path: impl AsRef<str>,
+	) -> Option<Self> {
+		let mut segments = path
+			.as_ref()
+			.split('/')
+			.filter(|segment| !segment.is_empty());
+		let first = segments.next()?;
+		let root = Self::root(sk, model)?;
+		let mut node = root
+			.siblings_from_here(sk)
+			.find(|node| node.name(sk).as_deref() == Some(first))?;
+		for segment in segments {
+			node = node.child(sk, segment)?;
+		}
+		Some(node)
+	}
+
+	fn wrap(&self, id: Option<ModelNodeId>) -> Option<Self> {
+		Some(Self {
+			model: self.model,
+			id: id?,
+		})
+	}
+
+	pub fn id(&self) -> ModelNodeId {
+		self.id
+	}
+	pub fn model(&self) -> &'a Model {
+		self.model
+	}
+
+	pub fn name(&self, sk: &impl StereoKitMultiThread) -> Option<String> {
+		sk.model_node_get_name(self.model, self.id)
+			.map(str::to_string)
+	}
+	pub fn set_name(&self, sk: &impl StereoKitMultiThread, name: impl AsRef<str>) {
+		sk.model_node_set_name(self.model, self.id, name)
+	}
+
+	/// The node above this one, or None if this is a root node.
+	pub fn parent(&self, sk: &impl StereoKitMultiThread) -> Option<Self> {
+		self.wrap(sk.model_node_parent(self.model, self.id))
+	}
+	/// The first node below this one.
+	pub fn first_child(&self, sk: &impl StereoKitMultiThread) -> Option<Self> {
+		self.wrap(sk.model_node_child(self.model, self.id))
+	}
+	/// The next node at the same level as this one.
+	pub fn next_sibling(&self, sk: &impl StereoKitMultiThread) -> Option<Self> {
+		self.wrap(sk.model_node_sibling(self.model, self.id))
+	}
+	/// The direct child with this name.
+	pub fn child(&self, sk: &impl StereoKitMultiThread, name: impl AsRef<str>) -> Option<Self> {
+		let name = name.as_ref();
+		self.children(sk)
+			.find(|child| child.name(sk).as_deref() == Some(name))
+	}
+	/// Finds a node below this one by the names along the way, separated by `/`.
+	pub fn child_path(
+		&self,
+		sk: &impl StereoKitMultiThread,
+		path: impl AsRef<str>,
+	) -> Option<Self> {
+		path.as_ref()
+			.split('/')
+			.filter(|segment| !segment.is_empty())
+			.try_fold(*self, |node, segment| node.child(sk, segment))
+	}
+	/// The names from the root down to this node, separated by `/`. This is
+	/// the reverse of [`ModelNode::find_path`].
+	pub fn path(&self, sk: &impl StereoKitMultiThread) -> String {
+		let mut names = vec![self.name(sk).unwrap_or_default()];
+		let mut node = self.parent(sk);
+		while let Some(parent) = node {
+			names.push(parent.name(sk).unwrap_or_default());
+			node = parent.parent(sk);
+		}
+		names.reverse();
+		names.join("/")
+	}
+
+	/// The nodes directly below this one.
+	pub fn children<'s, S: StereoKitMultiThread>(&self, sk: &'s S) -> Siblings<'a, 's, S> {
+		Siblings {
+			sk,
+			next: self.first_child(sk),
+		}
+	}
+	fn siblings_from_here<'s, S: StereoKitMultiThread>(&self, sk: &'s S) -> Siblings<'a, 's, S> {
+		Siblings {
+			sk,
+			next: Some(*self),
+		}
+	}
+	/// Every node below this one, depth first, so a node's whole subtree comes before its next sibling.
+	pub fn descendants<'s, S: StereoKitMultiThread>(&self, sk: &'s S) -> DepthFirst<'a, 's, S> {
+		DepthFirst {
+			sk,
+			stack: self.first_child(sk).into_iter().collect(),
+		}
+	}
+	/// Every node below this one, breadth first, so all the children come before any grandchildren.
+	pub fn descendants_breadth_first<'s, S: StereoKitMultiThread>(
+		&self,
+		sk: &'s S,
+	) -> BreadthFirst<'a, 's, S> {
+		BreadthFirst {
+			sk,
+			queue: self.children(sk).collect(),
+		}
+	}
+
+	/// Adds a new node at the end of this node's children.
+	pub fn add_child(
+		&self,
+		sk: &impl StereoKitMultiThread,
+		name: impl AsRef<str>,
+		local_transform: impl Into<Mat4>,
+		mesh: impl AsRef<Mesh>,
+		material: impl AsRef<Material>,
+	) -> Self {
+		let id =
+			sk.model_node_add_child(self.model, self.id, name, local_transform, mesh, material);
+		Self {
+			model: self.model,
+			id,
+		}
+	}
+
+	/// The transform relative to this node's parent.
+	pub fn transform_local(&self, sk: &impl StereoKitMultiThread) -> Mat4 {
+		sk.model_node_get_transform_local(self.model, self.id)
+	}
+	pub fn set_transform_local(&self, sk: &impl StereoKitMultiThread, transform: impl Into<Mat4>) {
+		sk.model_node_set_transform_local(self.model, self.id, transform)
+	}
+	/// The transform relative to the Model's origin.
+	pub fn transform_model(&self, sk: &impl StereoKitMultiThread) -> Mat4 {
+		sk.model_node_get_transform_model(self.model, self.id)
+	}
+	pub fn set_transform_model(&self, sk: &impl StereoKitMultiThread, transform: impl Into<Mat4>) {
+		sk.model_node_set_transform_model(self.model, self.id, transform)
+	}
+
+	pub fn mesh(&self, sk: &impl StereoKitMultiThread) -> Option<Mesh> {
+		sk.model_node_get_mesh(self.model, self.id)
+	}
+	pub fn set_mesh(&self, sk: &impl StereoKitMultiThread, mesh: impl AsRef<Mesh>) {
+		sk.model_node_set_mesh(self.model, self.id, mesh)
+	}
+	pub fn material(&self, sk: &impl StereoKitMultiThread) -> Option<Material> {
+		sk.model_node_get_material(self.model, self.id)
+	}
+	pub fn set_material(&self, sk: &impl StereoKitMultiThread, material: impl AsRef<Material>) {
+		sk.model_node_set_material(self.model, self.id, material)
+	}
+	pub fn visible(&self, sk: &impl StereoKitMultiThread) -> bool {
+		sk.model_node_get_visible(self.model, self.id)
+	}
+	pub fn set_visible(&self, sk: &impl StereoKitMultiThread, visible: bool) {
+		sk.model_node_set_visible(self.model, self.id, visible)
+	}
+	pub fn solid(&self, sk: &impl StereoKitMultiThread) -> bool {
+		sk.model_node_get_solid(self.model, self.id)
+	}
+	pub fn set_solid(&self, sk: &impl StereoKitMultiThread, solid: bool) {
+		sk.model_node_set_solid(self.model, self.id, solid)
+	}
+
+	/// The raw string stored under this key in the node's info, glTF extras
+	/// show up here when a Model is loaded.
+	pub fn info_str(&self, sk: &impl StereoKitMultiThread, key: impl AsRef<str>) -> Option<String> {
+		sk.model_node_info_get(self.model, self.id, key)
+			.map(str::to_string)
+	}
+	pub fn set_info_str(
+		&self,
+		sk: &impl StereoKitMultiThread,
+		key: impl AsRef<str>,
+		value: impl AsRef<str>,
+	) {
+		sk.model_node_info_set(self.model, self.id, key.as_ref(), value.as_ref())
+	}
+
+	/// Reads the info under this key as any serde type, stored as JSON! Values
+	/// that aren't JSON, like plain strings from glTF extras, are treated as a
+	/// JSON string, so `info::<String>` works on those too. Returns None if
+	/// the key isn't there.
+	pub fn info<T: DeserializeOwned>(
+		&self,
+		sk: &impl StereoKitMultiThread,
+		key: impl AsRef<str>,
+	) -> SkResult<Option<T>> {
+		let key = key.as_ref();
+		let Some(value) = self.info_str(sk, key) else {
+			return Ok(None);
+		};
+		serde_json::from_str(&value)
+			.or_else(|e| serde_json::from_value(serde_json::Value::String(value)).map_err(|_| e))
+			.map(Some)
+			.map_err(|e| StereoKitError::NodeInfo(key.to_string(), e.to_string()))
+	}
+	/// Stores any serde type as JSON in the node's info, read it back with [`ModelNode::info`].
+	pub fn set_info<T: Serialize>(
+		&self,
+		sk: &impl StereoKitMultiThread,
+		key: impl AsRef<str>,
+		value: &T,
+	) -> SkResult<()> {
+		let key = key.as_ref();
+		let json = serde_json::to_string(value)
+			.map_err(|e| StereoKitError::NodeInfo(key.to_string(), e.to_string()))?;
+		self.set_info_str(sk, key, json);
+		Ok(())
+	}
+	/// Removes a key from the node's info, returns false if it wasn't there.
+	pub fn remove_info(&self, sk: &impl StereoKitMultiThread, key: impl AsRef<str>) -> bool {
+		sk.model_node_info_remove(self.model, self.id, key)
+	}
+	pub fn clear_info(&self, sk: &impl StereoKitMultiThread) {
+		sk.model_node_info_clear(self.model, self.id)
+	}
+	pub fn info_count(&self, sk: &impl StereoKitMultiThread) -> i32 {
+		sk.model_node_info_count(self.model, self.id)
+	}
+	/// All the key/value pairs in this node's info.
+	pub fn info_iter<'s, S: StereoKitMultiThread>(&self, sk: &'s S) -> InfoIter<'a, 's, S> {
+		InfoIter {
+			sk,
+			node: *self,
+			iterator: 0,
+		}
+	}
+	/// Copies all of this node's info into a map.
+	pub fn info_map(&self, sk: &impl StereoKitMultiThread) -> HashMap<String, String> {
+		self.info_iter(sk).collect()
+	}
+}
+
+impl PartialEq for ModelNode<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		std::ptr::eq(self.model, other.model) && self.id == other.id
+	}
+}
+impl Eq for ModelNode<'_> {}
+
+impl std::fmt::Debug for ModelNode<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ModelNode").field("id", &self.id).finish()
+	}
+}
+
+/// Walks a chain of sibling nodes, see [`ModelNode::children`].
+pub struct Siblings<'a, 's, S: StereoKitMultiThread> {
+	sk: &'s S,
+	next: Option<ModelNode<'a>>,
+}
+impl<'a, S: StereoKitMultiThread> Iterator for Siblings<'a, '_, S> {
+	type Item = ModelNode<'a>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let node = self.next?;
+		self.next = node.next_sibling(self.sk);
+		Some(node)
+	}
+}
+
+/// See [`ModelNode::descendants`].
+pub struct DepthFirst<'a, 's, S: StereoKitMultiThread> {
+	sk: &'s S,
+	stack: Vec<ModelNode<'a>>,
+}
+impl<'a, S: StereoKitMultiThread> Iterator for DepthFirst<'a, '_, S> {
+	type Item = ModelNode<'a>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let node = self.stack.pop()?;
+		// The sibling goes on first so the child's subtree is visited before it
+		self.stack.extend(node.next_sibling(self.sk));
+		self.stack.extend(node.first_child(self.sk));
+		Some(node)
+	}
+}
+
+/// See [`ModelNode::descendants_breadth_first`].
+pub struct BreadthFirst<'a, 's, S: StereoKitMultiThread> {
+	sk: &'s S,
+	queue: VecDeque<ModelNode<'a>>,
+}
+impl<'a, S: StereoKitMultiThread> Iterator for BreadthFirst<'a, '_, S> {
+	type Item = ModelNode<'a>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let node = self.queue.pop_front()?;
+		self.queue.extend(node.children(self.sk));
+		Some(node)
+	}
+}
+
+/// See [`ModelNode::info_iter`]. Keys and values are copied out as they're
+/// visited, so changing the info mid iteration can't leave them dangling.
+/// Entries that aren't valid UTF-8 are skipped.
+pub struct InfoIter<'a, 's, S: StereoKitMultiThread> {
+	sk: &'s S,
+	node: ModelNode<'a>,
+	iterator: i32,
+}
+impl<S: StereoKitMultiThread> Iterator for InfoIter<'_, '_, S> {
+	type Item = (String, String);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let (key, value, iterator) =
+			self.sk
+				.model_node_info_iterate(self.node.model, self.iterator, self.node.id)?;
+		self.iterator = iterator;
+		Some((key.to_string(), value.to_string()))
+	}
+}
@@ -0,0 +1,211 @@
+use crate::camera::Camera;
+use crate::named_colors::BLACK;
+use crate::{
+	Material, RenderClear, RenderLayer, SkResult, StereoKitDraw, StereoKitMultiThread, Tex,
+	TextureFormat, TextureType,
+};
+use glam::Vec2;
+
+/// One step of a [`PostProcessChain`], a Material that gets blitted over the
+/// whole image. The image so far is bound to the Material's `source` texture
+/// parameter before each blit, or whichever one [`PostEffect::input`] names.
+pub struct PostEffect {
+	pub material: Material,
+	/// The texture parameter the previous step's output goes into.
+	pub input: String,
+	/// Disabled effects are skipped, without needing to rebuild the chain.
+	pub enabled: bool,
+}
+
+impl PostEffect {
+	/// Wraps a Material, reading its input from the `source` texture parameter.
+	pub fn new(material: Material) -> Self {
+		Self {
+			material,
+			input: "source".to_string(),
+			enabled: true,
+		}
+	}
+
+	/// ACES filmic tone mapping, from HDR into the displayable range.
+	pub fn tone_mapping(sk: &impl StereoKitMultiThread, exposure: f32) -> SkResult<Self> {
+		let shader = crate::include_compiled_shader!("stereokit_rs/post_tonemap", "post_tonemap")
+			.shader(sk)?;
+		let material = sk.material_create(shader);
+		sk.material_set_float(&material, "exposure", exposure);
+		Ok(Self::new(material))
+	}
+
+	/// Darkens the edges of the image, `radius` is where the darkening starts,
+	/// with 1 being the corners.
+	pub fn vignette(sk: &impl StereoKitMultiThread, strength: f32, radius: f32) -> SkResult<Self> {
+		let shader = crate::include_compiled_shader!("stereokit_rs/post_vignette", "post_vignette")
+			.shader(sk)?;
+		let material = sk.material_create(shader);
+		sk.material_set_float(&material, "strength", strength);
+		sk.material_set_float(&material, "radius", radius);
+		Ok(Self::new(material))
+	}
+
+	/// A gaussian blur, which takes two passes, horizontal then vertical.
+	/// `radius` is the spacing between samples in pixels.
+	pub fn blur(sk: &impl StereoKitMultiThread, radius: f32) -> SkResult<[Self; 2]> {
+		let shader =
+			crate::include_compiled_shader!("stereokit_rs/post_blur", "post_blur").shader(sk)?;
+		Ok([Vec2::X, Vec2::Y].map(|direction| {
+			let material = sk.material_create(&shader);
+			sk.material_set_vector2(&material, "direction", direction);
+			sk.material_set_float(&material, "radius", radius);
+			Self::new(material)
+		}))
+	}
+}
+
+/// Runs a list of [`PostEffect`]s one after another with `render_blit`,
+/// bouncing between a pair of render targets it owns! The targets are sized
+/// to the display by default, and resized whenever it changes.
+///
+/// `render_blit` happens right away, while `render_to` happens at the end of
+/// the frame, so a scene rendered with [`PostProcessChain::render_scene`]
+/// is ready to run through the chain on the following frame.
+pub struct PostProcessChain {
+	pub effects: Vec<PostEffect>,
+	/// The format of the targets, a float format keeps HDR color around for tone mapping.
+	pub format: TextureFormat,
+	/// Sizes the targets to this instead of the display, when set.
+	pub size: Option<(i32, i32)>,
+	scene: Option<Tex>,
+	targets: Option<[Tex; 2]>,
+	target_size: (i32, i32),
+}
+
+impl PostProcessChain {
+	pub fn new() -> Self {
+		Self {
+			effects: Vec::new(),
+			format: TextureFormat::RGBA64F,
+			size: None,
+			scene: None,
+			targets: None,
+			target_size: (0, 0),
+		}
+	}
+
+	pub fn with_effect(mut self, effect: PostEffect) -> Self {
+		self.effects.push(effect);
+		self
+	}
+
+	pub fn push(&mut self, effect: PostEffect) {
+		self.effects.push(effect);
+	}
+
+	fn create_target(&self, sk: &impl StereoKitMultiThread, zbuffer: bool) -> Tex {
+		let (width, height) = self.target_size;
+		let tex = sk.tex_gen_color(
+			BLACK,
+			width,
+			height,
+			TextureType::RENDER_TARGET,
+			self.format,
+		);
+		if zbuffer {
+			sk.tex_add_zbuffer(&tex, TextureFormat::Depth32);
+		}
+		tex
+	}
+
+	/// Makes sure the targets exist and match the size we want, recreating them if they don't.
+	fn ensure_targets(&mut self, sk: &impl StereoKitMultiThread) {
+		let size = self.size.unwrap_or_else(|| {
+			(
+				sk.device_display_get_width(),
+				sk.device_display_get_height(),
+			)
+		});
+		if size != self.target_size || self.scene.is_none() || self.targets.is_none() {
+			// Nothing else holds these, and their zbuffers go with them
+			for target in self
+				.scene
+				.take()
+				.into_iter()
+				.chain(self.targets.take().into_iter().flatten())
+			{
+				unsafe { sk.tex_release(target) };
+			}
+			self.target_size = size;
+			self.scene = Some(self.create_target(sk, true));
+			self.targets = Some([self.create_target(sk, false), self.create_target(sk, false)]);
+		}
+	}
+
+	/// The target the scene gets rendered into, render into this yourself if
+	/// [`PostProcessChain::render_scene`] doesn't fit your needs.
+	pub fn scene_target(&mut self, sk: &impl StereoKitMultiThread) -> &Tex {
+		self.ensure_targets(sk);
+		self.scene.as_ref().unwrap()
+	}
+
+	/// Queues up a render of the scene from this camera into the scene target.
+	pub fn render_scene(
+		&mut self,
+		sk: &impl StereoKitDraw,
+		camera: &Camera,
+		layer_filter: RenderLayer,
+	) {
+		let scene = self.scene_target(sk);
+		camera.render_to(sk, scene, layer_filter, RenderClear::All);
+	}
+
+	/// Runs the scene target through every enabled effect, and returns the
+	/// result. If there are no enabled effects, that's the scene target itself.
+	pub fn run(&mut self, sk: &impl StereoKitDraw) -> &Tex {
+		self.ensure_targets(sk);
+		let scene = self.scene.as_ref().unwrap();
+		let targets = self.targets.as_ref().unwrap();
+
+		let mut input = scene;
+		for (index, effect) in self
+			.effects
+			.iter()
+			.filter(|effect| effect.enabled)
+			.enumerate()
+		{
+			let output = &targets[index % 2];
+			sk.material_set_texture(&effect.material, &effect.input, input);
+			sk.render_blit(output, &effect.material);
+			input = output;
+		}
+		input
+	}
+
+	/// Runs the chain, and then copies the result into `output` with
+	/// `material`, which should read from its `source` texture. This is how
+	/// the result gets onto a texture that's shown somewhere, like on a quad or
+	/// a swapchain from `tex_set_surface`.
+	pub fn present(
+		&mut self,
+		sk: &impl StereoKitDraw,
+		output: impl AsRef<Tex>,
+		material: impl AsRef<Material>,
+	) {
+		let result = self.run(sk);
+		sk.material_set_texture(&material, "source", result);
+		sk.render_blit(output, material);
+	}
+}
+
+impl Default for PostProcessChain {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Drop for PostProcessChain {
+	fn drop(&mut self) {
+		// Nothing else holds the targets, so they'd leak otherwise
+		for target in self.scene.iter().chain(self.targets.iter().flatten()) {
+			unsafe { crate::tex_release(target) };
+		}
+	}
+}
@@ -0,0 +1,404 @@
+use crate::named_colors::WHITE;
+use crate::{Color128, StereoKitDraw, StereoKitMultiThread, TextAlign, TextFit, TextStyle};
+use glam::{Mat4, Quat, Vec2, Vec3};
+
+/// A piece of text that's all drawn with the same style.
+#[derive(Debug, Clone)]
+pub struct TextSpan {
+	pub text: String,
+	pub style: TextStyle,
+	/// Multiplied with the style's own color, linear space.
+	pub tint: Color128,
+}
+
+impl TextSpan {
+	pub fn new(text: impl Into<String>, style: TextStyle) -> Self {
+		Self {
+			text: text.into(),
+			style,
+			tint: WHITE,
+		}
+	}
+
+	pub fn tinted(text: impl Into<String>, style: TextStyle, tint: Color128) -> Self {
+		Self {
+			text: text.into(),
+			style,
+			tint,
+		}
+	}
+}
+
+/// Text made from several [`TextSpan`]s, so a paragraph can mix bold, colored
+/// or bigger words! Spans flow one after another, and `\n` starts a new line.
+/// Lay it out inside a box with [`RichText::layout`], then draw the layout as
+/// often as you like, or do both at once with [`RichText::draw`].
+#[derive(Debug, Clone)]
+pub struct RichText {
+	pub spans: Vec<TextSpan>,
+	/// Where each line sits horizontally, and where the whole block sits vertically.
+	pub align: TextAlign,
+	pub fit: TextFit,
+	/// Multiplies the height of each line, 1 is the style's character height.
+	pub line_spacing: f32,
+}
+
+impl Default for RichText {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl RichText {
+	pub fn new() -> Self {
+		Self {
+			spans: Vec::new(),
+			align: TextAlign::Left,
+			fit: TextFit::Wrap,
+			line_spacing: 1.0,
+		}
+	}
+
+	pub fn with_span(mut self, text: impl Into<String>, style: TextStyle) -> Self {
+		self.spans.push(TextSpan::new(text, style));
+		self
+	}
+
+	pub fn with_tinted_span(
+		mut self,
+		text: impl Into<String>,
+		style: TextStyle,
+		tint: Color128,
+	) -> Self {
+		self.spans.push(TextSpan::tinted(text, style, tint));
+		self
+	}
+
+	pub fn with_align(mut self, align: TextAlign) -> Self {
+		self.align = align;
+		self
+	}
+
+	pub fn with_fit(mut self, fit: TextFit) -> Self {
+		self.fit = fit;
+		self
+	}
+
+	pub fn with_line_spacing(mut self, line_spacing: f32) -> Self {
+		self.line_spacing = line_spacing;
+		self
+	}
+
+	pub fn push(&mut self, span: TextSpan) {
+		self.spans.push(span);
+	}
+
+	/// Measures the spans with `text_size`, and arranges them in a box of
+	/// `size` meters following `fit` and `align`:
+	///
+	/// - `Wrap` breaks lines between words, or inside a word that's wider than the box.
+	/// - `Clip` only breaks at `\n`, and cuts off whatever goes past the box.
+	/// - `Squeeze` and `Exact` only break at `\n`, then scale everything to fit the box.
+	/// - `Overflow` only breaks at `\n`, and ignores the box's size.
+	pub fn layout(&self, sk: &impl StereoKitMultiThread, size: impl Into<Vec2>) -> RichTextLayout {
+		let size = size.into();
+		let wrap = self.fit == TextFit::Wrap;
+		let clip = self.fit == TextFit::Clip;
+
+		let mut lines = vec![Line::default()];
+		for (index, span) in self.spans.iter().enumerate() {
+			let height = sk.text_style_get_char_height(span.style) * self.line_spacing;
+			for (paragraph_index, paragraph) in span.text.split('\n').enumerate() {
+				if paragraph_index > 0 {
+					lines.push(Line::default());
+				}
+				lines.last_mut().unwrap().fit_height(height);
+
+				for token in tokens(paragraph.trim_end_matches('\r')) {
+					let line = lines.last_mut().unwrap();
+					if line.clipped {
+						continue;
+					}
+					let word = token.trim_end();
+					let word_width = measure(sk, word, span.style);
+
+					if wrap && !line.runs.is_empty() && line.width + word_width > size.x {
+						lines.push(Line::default());
+						lines.last_mut().unwrap().fit_height(height);
+					}
+					let line = lines.last_mut().unwrap();
+					let available = size.x - line.width;
+					if (wrap || clip) && word_width > available {
+						// Wrapping a word that won't fit on a line of its own, or
+						// clipping, both mean cutting the word where the line ends
+						let mut rest = token;
+						loop {
+							let line = lines.last_mut().unwrap();
+							let available = size.x - line.width;
+							let rest_width = measure(sk, rest.trim_end(), span.style);
+							if rest_width <= available {
+								let full_width = measure(sk, rest, span.style);
+								line.push(index, rest, rest_width, full_width, height);
+								break;
+							}
+							let split =
+								fit_prefix(rest, available, |text| measure(sk, text, span.style));
+							let piece = &rest[..split];
+							let piece_width = measure(sk, piece, span.style);
+							// A clipped line doesn't get its one character of progress
+							if !piece.is_empty() && (!clip || piece_width <= available) {
+								line.push(index, piece, piece_width, piece_width, height);
+							}
+							if clip {
+								line.clipped = true;
+								break;
+							}
+							rest = &rest[split..];
+							lines.push(Line::default());
+							lines.last_mut().unwrap().fit_height(height);
+						}
+					} else {
+						let full_width = measure(sk, token, span.style);
+						line.push(index, token, word_width, full_width, height);
+					}
+				}
+			}
+		}
+
+		let content = Vec2::new(
+			lines
+				.iter()
+				.map(|line| line.trimmed_width)
+				.fold(0.0, f32::max),
+			lines.iter().map(|line| line.height).sum(),
+		);
+		let fits = |content: f32, space: f32| if content > 0.0 { space / content } else { 1.0 };
+		let scale = match self.fit {
+			TextFit::Squeeze => fits(content.x, size.x)
+				.min(fits(content.y, size.y))
+				.min(1.0),
+			TextFit::Exact => fits(content.x, size.x).min(fits(content.y, size.y)),
+			_ => 1.0,
+		};
+		let scaled = self.fit == TextFit::Squeeze || self.fit == TextFit::Exact;
+
+		let align = self.align as u32;
+		let factor = |center: TextAlign, end: TextAlign| {
+			if align & end as u32 != 0 {
+				1.0
+			} else if align & center as u32 != 0 {
+				0.5
+			} else {
+				0.0
+			}
+		};
+		let x_factor = factor(TextAlign::XCenter, TextAlign::XRight);
+		let y_factor = factor(TextAlign::YCenter, TextAlign::YBottom);
+		let line_space = if scaled { content.x } else { size.x };
+		let block = Vec2::new(
+			if scaled {
+				(size.x - content.x * scale) * x_factor
+			} else {
+				0.0
+			},
+			(size.y - content.y * scale) * y_factor,
+		);
+
+		let mut runs = Vec::new();
+		let mut top = 0.0;
+		for line in lines {
+			if clip && top + line.height > size.y {
+				break;
+			}
+			let line_x = (line_space - line.trimmed_width) * x_factor;
+			for run in line.runs {
+				let span = &self.spans[run.span];
+				let height = sk.text_style_get_char_height(span.style) * self.line_spacing;
+				// Lines up the bottoms of different sized spans
+				let position = Vec2::new(line_x + run.x, top + line.height - height);
+				runs.push(PlacedRun {
+					text: run.text,
+					style: span.style,
+					tint: span.tint,
+					position: block + position * scale,
+					size: Vec2::new(run.width, height) * scale,
+				});
+			}
+			top += line.height;
+		}
+
+		RichTextLayout {
+			runs,
+			size: content * scale,
+			scale,
+		}
+	}
+
+	/// Lays out and draws the text in one go, with the top left corner of the
+	/// box at `transform`. Returns the size the text took up, in meters.
+	pub fn draw(
+		&self,
+		sk: &impl StereoKitDraw,
+		transform: impl Into<Mat4>,
+		size: impl Into<Vec2>,
+	) -> Vec2 {
+		let layout = self.layout(sk, size);
+		layout.draw(sk, transform);
+		layout.size
+	}
+}
+
+/// A bit of text with one style, placed by [`RichText::layout`].
+#[derive(Debug, Clone)]
+pub struct PlacedRun {
+	pub text: String,
+	pub style: TextStyle,
+	pub tint: Color128,
+	/// The run's top left corner, in meters right and down from the top left of the box.
+	pub position: Vec2,
+	pub size: Vec2,
+}
+
+/// [`RichText`] that's been measured and arranged, ready to draw.
+#[derive(Debug, Clone)]
+pub struct RichTextLayout {
+	pub runs: Vec<PlacedRun>,
+	/// The size of all the text together, in meters.
+	pub size: Vec2,
+	/// How much `Squeeze` or `Exact` scaled the text, 1 otherwise.
+	pub scale: f32,
+}
+
+impl RichTextLayout {
+	/// Draws every run, with the top left corner of the box at `transform`.
+	pub fn draw(&self, sk: &impl StereoKitDraw, transform: impl Into<Mat4>) {
+		let transform = transform.into();
+		for run in &self.runs {
+			// Text runs right along -X, same as the rest of StereoKit's UI
+			let local = Mat4::from_scale_rotation_translation(
+				Vec3::splat(self.scale),
+				Quat::IDENTITY,
+				Vec3::new(-run.position.x, -run.position.y, 0.0),
+			);
+			sk.text_add_at(
+				&run.text,
+				transform * local,
+				run.style,
+				TextAlign::Left,
+				TextAlign::Left,
+				Vec3::ZERO,
+				run.tint,
+			);
+		}
+	}
+}
+
+struct LineRun {
+	span: usize,
+	text: String,
+	x: f32,
+	width: f32,
+}
+
+#[derive(Default)]
+struct Line {
+	runs: Vec<LineRun>,
+	width: f32,
+	/// The width without any whitespace on the end, this is what gets aligned.
+	trimmed_width: f32,
+	height: f32,
+	clipped: bool,
+}
+
+impl Line {
+	fn fit_height(&mut self, height: f32) {
+		self.height = self.height.max(height);
+	}
+
+	fn push(&mut self, span: usize, text: &str, word_width: f32, full_width: f32, height: f32) {
+		self.fit_height(height);
+		match self.runs.last_mut() {
+			Some(run) if run.span == span => {
+				run.text.push_str(text);
+				run.width = self.width + full_width - run.x;
+			}
+			_ => self.runs.push(LineRun {
+				span,
+				text: text.to_string(),
+				x: self.width,
+				width: full_width,
+			}),
+		}
+		if word_width > 0.0 {
+			self.trimmed_width = self.width + word_width;
+		}
+		self.width += full_width;
+	}
+}
+
+fn measure(sk: &impl StereoKitMultiThread, text: &str, style: TextStyle) -> f32 {
+	if text.is_empty() {
+		0.0
+	} else {
+		sk.text_size(text, style).x
+	}
+}
+
+/// Splits text into words, each keeping the whitespace that follows it.
+fn tokens(text: &str) -> impl Iterator<Item = &str> {
+	let mut rest = text;
+	std::iter::from_fn(move || {
+		if rest.is_empty() {
+			return None;
+		}
+		let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+		let end = rest[word_end..]
+			.find(|c: char| !c.is_whitespace())
+			.map(|i| word_end + i)
+			.unwrap_or(rest.len());
+		let (token, remaining) = rest.split_at(end);
+		rest = remaining;
+		Some(token)
+	})
+}
+
+/// The byte length of the longest start of `text` that `measure` says fits in
+/// `width`. If not even one character fits, that's still one character, so
+/// lines always make progress.
+fn fit_prefix(text: &str, width: f32, measure: impl Fn(&str) -> f32) -> usize {
+	let mut ends = text.char_indices().map(|(i, c)| i + c.len_utf8());
+	let mut fit = ends.next().unwrap_or(0);
+	for end in ends {
+		if measure(&text[..end]) > width {
+			break;
+		}
+		fit = end;
+	}
+	fit
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn splits_tokens() {
+		let words: Vec<&str> = tokens("hello  world\n foo").collect();
+		assert_eq!(words, ["hello  ", "world\n ", "foo"]);
+		let words: Vec<&str> = tokens("  lead").collect();
+		assert_eq!(words, ["  ", "lead"]);
+		assert_eq!(tokens("").count(), 0);
+	}
+
+	#[test]
+	fn fits_prefix() {
+		let chars = |text: &str| text.chars().count() as f32;
+		assert_eq!(fit_prefix("abcdef", 3.0, chars), 3);
+		assert_eq!(fit_prefix("abc", 10.0, chars), 3);
+		// Always at least one character, so wrapping makes progress
+		assert_eq!(fit_prefix("abc", 0.0, chars), 1);
+		// Lengths are in bytes, and never split a character
+		assert_eq!(fit_prefix("héllo", 2.0, chars), "hé".len());
+		assert_eq!(fit_prefix("", 1.0, chars), 0);
+	}
+}
@@ -0,0 +1,358 @@
+use crate::material_desc::{apply_param, AssetSource, MaterialParam};
+use crate::named_colors::WHITE;
+use crate::{
+	Material, Model, Pose, RenderLayer, SkResult, Sound, SoundInstance, StereoKitDraw,
+	StereoKitError, StereoKitMultiThread, Transparency,
+};
+use glam::{Mat4, Vec3};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelDesc {
+	pub name: String,
+	pub source: AssetSource,
+	/// Swaps the material of subsets with these names for scene materials.
+	#[serde(default)]
+	pub material_overrides: HashMap<String, String>,
+}
+
+/// A scene material, made by copying an existing material and changing a few
+/// parameters. For building one from a shader, see
+/// [`MaterialDesc`](crate::material_desc::MaterialDesc).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MaterialCopyDesc {
+	pub name: String,
+	/// The id of the material to copy, like "default/material".
+	pub copy_of: String,
+	#[serde(default)]
+	pub transparency: Option<Transparency>,
+	#[serde(default)]
+	pub params: HashMap<String, MaterialParam>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SoundDesc {
+	pub name: String,
+	pub file: PathBuf,
+}
+
+fn default_pose() -> Pose {
+	Pose::IDENTITY
+}
+fn default_scale() -> Vec3 {
+	Vec3::ONE
+}
+fn default_volume() -> f32 {
+	1.0
+}
+
+/// A node in the scene hierarchy, its pose and scale are relative to its parent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeDesc {
+	pub name: String,
+	#[serde(default = "default_pose")]
+	pub pose: Pose,
+	#[serde(default = "default_scale")]
+	pub scale: Vec3,
+	/// Name of a model in the scene's model list.
+	#[serde(default)]
+	pub model: Option<String>,
+	#[serde(default)]
+	pub layer: RenderLayer,
+	/// Name of a sound in the scene's sound list, played by [`Scene::play_sounds`].
+	#[serde(default)]
+	pub sound: Option<String>,
+	#[serde(default = "default_volume")]
+	pub volume: f32,
+	#[serde(default)]
+	pub children: Vec<NodeDesc>,
+}
+impl NodeDesc {
+	/// This node's transform relative to its parent.
+	pub fn local_transform(&self) -> Mat4 {
+		Mat4::from_scale_rotation_translation(self.scale, self.pose.orientation, self.pose.position)
+	}
+}
+
+/// A declarative description of a scene! This is plain serde data, so it can
+/// be written by hand in JSON (or RON, with the `ron` feature), and turned
+/// into live assets with [`Scene::load`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SceneDesc {
+	#[serde(default)]
+	pub models: Vec<ModelDesc>,
+	#[serde(default)]
+	pub materials: Vec<MaterialCopyDesc>,
+	#[serde(default)]
+	pub sounds: Vec<SoundDesc>,
+	#[serde(default)]
+	pub nodes: Vec<NodeDesc>,
+}
+impl SceneDesc {
+	pub fn from_json(json: &str) -> SkResult<Self> {
+		serde_json::from_str(json).map_err(|e| StereoKitError::SceneParse(e.to_string()))
+	}
+	pub fn to_json(&self) -> SkResult<String> {
+		serde_json::to_string_pretty(self).map_err(|e| StereoKitError::SceneParse(e.to_string()))
+	}
+	#[cfg(feature = "ron")]
+	pub fn from_ron(ron: &str) -> SkResult<Self> {
+		ron::from_str(ron).map_err(|e| StereoKitError::SceneParse(e.to_string()))
+	}
+	#[cfg(feature = "ron")]
+	pub fn to_ron(&self) -> SkResult<String> {
+		ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+			.map_err(|e| StereoKitError::SceneParse(e.to_string()))
+	}
+
+	/// Reads a scene file, using the extension to pick the format. Files ending
+	/// in .ron are read as RON, everything else as JSON.
+	pub fn load_file(path: impl AsRef<Path>) -> SkResult<Self> {
+		let path = path.as_ref();
+		let text = std::fs::read_to_string(path)
+			.map_err(|e| StereoKitError::SceneFile(path.to_path_buf(), e.to_string()))?;
+		match path.extension().and_then(|ext| ext.to_str()) {
+			#[cfg(feature = "ron")]
+			Some("ron") => Self::from_ron(&text),
+			_ => Self::from_json(&text),
+		}
+	}
+
+	/// Makes sure every model, sound and material a node or override names is
+	/// in the description, so a typo is an error rather than a silent gap.
+	fn check_references(&self) -> SkResult<()> {
+		fn missing(kind: &str, name: &str, user: &str) -> StereoKitError {
+			StereoKitError::SceneParse(format!(
+				"{user} uses {kind} \"{name}\", which isn't in the scene"
+			))
+		}
+		fn check_nodes(desc: &SceneDesc, nodes: &[NodeDesc]) -> SkResult<()> {
+			for node in nodes {
+				if let Some(model) = &node.model {
+					if !desc.models.iter().any(|desc| &desc.name == model) {
+						return Err(missing("model", model, &format!("node \"{}\"", node.name)));
+					}
+				}
+				if let Some(sound) = &node.sound {
+					if !desc.sounds.iter().any(|desc| &desc.name == sound) {
+						return Err(missing("sound", sound, &format!("node \"{}\"", node.name)));
+					}
+				}
+				check_nodes(desc, &node.children)?;
+			}
+			Ok(())
+		}
+
+		for model_desc in &self.models {
+			for material in model_desc.material_overrides.values() {
+				if !self.materials.iter().any(|desc| &desc.name == material) {
+					let user = format!("model \"{}\"", model_desc.name);
+					return Err(missing("material", material, &user));
+				}
+			}
+		}
+		check_nodes(self, &self.nodes)
+	}
+
+	/// Writes this scene to a file, picking the format the same way as [`SceneDesc::load_file`].
+	pub fn save_file(&self, path: impl AsRef<Path>) -> SkResult<()> {
+		let path = path.as_ref();
+		let text = match path.extension().and_then(|ext| ext.to_str()) {
+			#[cfg(feature = "ron")]
+			Some("ron") => self.to_ron()?,
+			_ => self.to_json()?,
+		};
+		std::fs::write(path, text)
+			.map_err(|e| StereoKitError::SceneFile(path.to_path_buf(), e.to_string()))
+	}
+}
+
+/// A [`SceneDesc`] with all of its assets loaded and ready to draw! The
+/// description stays editable, so you can move nodes around at runtime and
+/// save the result back out with [`Scene::save_file`].
+pub struct Scene {
+	pub desc: SceneDesc,
+	pub models: HashMap<String, Model>,
+	pub materials: HashMap<String, Material>,
+	pub sounds: HashMap<String, Sound>,
+}
+impl Scene {
+	/// Loads every asset the description references, materials first so models can use them.
+	/// Nodes and overrides that name something the description doesn't have are an error.
+	pub fn load(sk: &impl StereoKitMultiThread, desc: SceneDesc) -> SkResult<Self> {
+		desc.check_references()?;
+		let mut materials = HashMap::new();
+		for material_desc in &desc.materials {
+			let material = sk.material_copy(sk.material_find(material_desc.copy_of.clone())?);
+			if let Some(transparency) = material_desc.transparency {
+				sk.material_set_transparency(&material, transparency);
+			}
+			for (name, param) in &material_desc.params {
+				apply_param(sk, &material, name, param)?;
+			}
+			materials.insert(material_desc.name.clone(), material);
+		}
+
+		let mut models = HashMap::new();
+		for model_desc in &desc.models {
+			let mut model = match &model_desc.source {
+				AssetSource::File(path) => sk.model_create_file(path, None::<crate::Shader>)?,
+				AssetSource::Id(id) => sk.model_find(id.clone())?,
+			};
+			if !model_desc.material_overrides.is_empty() {
+				// Models are shared by id, so override a copy rather than every user's materials
+				model = sk.model_copy(&model);
+				for subset in 0..sk.model_subset_count(&model) {
+					let subset_name = sk.model_get_name(&model, subset);
+					if let Some(material) = model_desc
+						.material_overrides
+						.get(subset_name)
+						.and_then(|name| materials.get(name))
+					{
+						sk.model_set_material(&model, subset, material);
+					}
+				}
+			}
+			models.insert(model_desc.name.clone(), model);
+		}
+
+		let mut sounds = HashMap::new();
+		for sound_desc in &desc.sounds {
+			sounds.insert(sound_desc.name.clone(), sk.sound_create(&sound_desc.file)?);
+		}
+
+		Ok(Self {
+			desc,
+			models,
+			materials,
+			sounds,
+		})
+	}
+
+	/// Reads a scene file and loads it, see [`SceneDesc::load_file`].
+	pub fn load_file(sk: &impl StereoKitMultiThread, path: impl AsRef<Path>) -> SkResult<Self> {
+		Self::load(sk, SceneDesc::load_file(path)?)
+	}
+
+	/// Saves the current state of the scene description.
+	pub fn save_file(&self, path: impl AsRef<Path>) -> SkResult<()> {
+		self.desc.save_file(path)
+	}
+
+	/// Finds the first node with this name, searching depth first.
+	pub fn find_node(&self, name: &str) -> Option<&NodeDesc> {
+		fn find<'a>(nodes: &'a [NodeDesc], name: &str) -> Option<&'a NodeDesc> {
+			nodes.iter().find_map(|node| {
+				(node.name == name)
+					.then_some(node)
+					.or_else(|| find(&node.children, name))
+			})
+		}
+		find(&self.desc.nodes, name)
+	}
+
+	/// Finds the first node with this name so you can move it around, searching depth first.
+	pub fn find_node_mut(&mut self, name: &str) -> Option<&mut NodeDesc> {
+		fn find<'a>(nodes: &'a mut [NodeDesc], name: &str) -> Option<&'a mut NodeDesc> {
+			for node in nodes {
+				if node.name == name {
+					return Some(node);
+				}
+				if let Some(found) = find(&mut node.children, name) {
+					return Some(found);
+				}
+			}
+			None
+		}
+		find(&mut self.desc.nodes, name)
+	}
+
+	/// Plays the sound of every node that has one, from that node's world position.
+	pub fn play_sounds(
+		&self,
+		sk: &impl StereoKitMultiThread,
+		root: impl Into<Mat4>,
+	) -> Vec<SoundInstance> {
+		let mut instances = Vec::new();
+		self.visit(root.into(), &mut |node, transform| {
+			if let Some(sound) = node.sound.as_ref().and_then(|name| self.sounds.get(name)) {
+				instances.push(sk.sound_play(
+					sound,
+					transform.transform_point3(Vec3::ZERO),
+					node.volume,
+				));
+			}
+		});
+		instances
+	}
+
+	fn visit(&self, root: Mat4, on_node: &mut impl FnMut(&NodeDesc, Mat4)) {
+		fn visit_nodes(
+			nodes: &[NodeDesc],
+			parent: Mat4,
+			on_node: &mut impl FnMut(&NodeDesc, Mat4),
+		) {
+			for node in nodes {
+				let transform = parent * node.local_transform();
+				on_node(node, transform);
+				visit_nodes(&node.children, transform, on_node);
+			}
+		}
+		visit_nodes(&self.desc.nodes, root, on_node);
+	}
+}
+
+/// Draws every node in the scene that has a model, relative to `root`.
+pub fn draw_scene(sk: &impl StereoKitDraw, scene: &Scene, root: impl Into<Mat4>) {
+	scene.visit(root.into(), &mut |node, transform| {
+		if let Some(model) = node.model.as_ref().and_then(|name| scene.models.get(name)) {
+			sk.model_draw(model, transform, WHITE, node.layer);
+		}
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn scene(json: &str) -> SceneDesc {
+		SceneDesc::from_json(json).unwrap()
+	}
+
+	#[test]
+	fn references() {
+		let desc = scene(
+			r#"{
+				"models": [{ "name": "box", "source": { "Id": "default/model" },
+					"material_overrides": { "lid": "red" } }],
+				"materials": [{ "name": "red", "copy_of": "default/material" }],
+				"sounds": [{ "name": "click", "file": "click.wav" }],
+				"nodes": [{ "name": "root", "model": "box",
+					"children": [{ "name": "child", "sound": "click" }] }]
+			}"#,
+		);
+		assert!(desc.check_references().is_ok());
+	}
+
+	#[test]
+	fn missing_references() {
+		let missing = |json: &str| match scene(json).check_references() {
+			Err(StereoKitError::SceneParse(message)) => message,
+			other => panic!("expected a SceneParse error, got {other:?}"),
+		};
+		assert!(missing(
+			r#"{ "nodes": [{ "name": "a", "children": [{ "name": "b", "model": "box" }] }] }"#
+		)
+		.contains("\"box\""));
+		assert!(
+			missing(r#"{ "nodes": [{ "name": "a", "sound": "click" }] }"#).contains("\"click\"")
+		);
+		assert!(missing(
+			r#"{ "models": [{ "name": "box", "source": { "Id": "default/model" },
+				"material_overrides": { "lid": "red" } }] }"#
+		)
+		.contains("\"red\""));
+	}
+}
@@ -0,0 +1,160 @@
+//! Compiles .hlsl shaders from a build script, for use with
+//! [`include_compiled_shader!`](crate::include_compiled_shader). This only
+//! needs StereoKit's `skshaderc` tool, which comes with StereoKit's releases.
+//!
+//! It's behind the `shader-build` feature, since it's only for build scripts.
+//! Keep in mind that a build-dependency on this crate builds StereoKit for the
+//! host as well. This file has no dependencies, so a build script can also
+//! carry its own copy of it, the way this crate's own build script does.
+//!
+//! ```ignore
+//! // Cargo.toml
+//! // [build-dependencies]
+//! // stereokit = { version = "...", features = ["shader-build"] }
+//!
+//! // build.rs
+//! fn main() {
+//!     stereokit::shader_build::ShaderCompiler::new().compile();
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Runs `skshaderc` on every .hlsl file in a folder. Anything that goes wrong
+/// is reported as a cargo warning rather than failing the build script, the
+/// build still stops at the `include_compiled_shader!` for a shader that
+/// didn't compile.
+#[derive(Debug, Clone)]
+pub struct ShaderCompiler {
+	compiler: PathBuf,
+	source_dir: PathBuf,
+	out_dir: PathBuf,
+	include_dirs: Vec<PathBuf>,
+	args: Vec<String>,
+}
+
+impl Default for ShaderCompiler {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl ShaderCompiler {
+	/// Compiles the crate's `shaders/` folder into `$OUT_DIR/shaders`. The
+	/// compiler is `skshaderc` from the PATH, or wherever the `SKSHADERC`
+	/// environment variable points.
+	pub fn new() -> Self {
+		let env_path = |name: &str| std::env::var_os(name).map(PathBuf::from);
+		Self {
+			compiler: env_path("SKSHADERC").unwrap_or_else(|| PathBuf::from("skshaderc")),
+			source_dir: env_path("CARGO_MANIFEST_DIR")
+				.unwrap_or_default()
+				.join("shaders"),
+			out_dir: env_path("OUT_DIR").unwrap_or_default().join("shaders"),
+			include_dirs: Vec::new(),
+			args: Vec::new(),
+		}
+	}
+
+	pub fn compiler(mut self, path: impl Into<PathBuf>) -> Self {
+		self.compiler = path.into();
+		self
+	}
+
+	pub fn source_dir(mut self, path: impl Into<PathBuf>) -> Self {
+		self.source_dir = path.into();
+		self
+	}
+
+	pub fn out_dir(mut self, path: impl Into<PathBuf>) -> Self {
+		self.out_dir = path.into();
+		self
+	}
+
+	/// A folder to look in for `#include`d files, like StereoKit's `stereokit.hlsli`.
+	pub fn include_dir(mut self, path: impl Into<PathBuf>) -> Self {
+		self.include_dirs.push(path.into());
+		self
+	}
+
+	/// An extra command line argument for `skshaderc`.
+	pub fn arg(mut self, arg: impl Into<String>) -> Self {
+		self.args.push(arg.into());
+		self
+	}
+
+	/// Compiles every shader, returning the paths of the ones that compiled.
+	pub fn compile(&self) -> Vec<PathBuf> {
+		println!("cargo:rerun-if-changed={}", self.source_dir.display());
+		println!("cargo:rerun-if-env-changed=SKSHADERC");
+
+		let sources = match std::fs::read_dir(&self.source_dir) {
+			Ok(entries) => {
+				let mut sources: Vec<PathBuf> = entries
+					.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+					.filter(|path| path.extension().map_or(false, |ext| ext == "hlsl"))
+					.collect();
+				sources.sort();
+				sources
+			}
+			Err(e) => {
+				warn(format!("couldn't read {}: {e}", self.source_dir.display()));
+				return Vec::new();
+			}
+		};
+		if let Err(e) = std::fs::create_dir_all(&self.out_dir) {
+			warn(format!("couldn't create {}: {e}", self.out_dir.display()));
+			return Vec::new();
+		}
+
+		sources
+			.iter()
+			.filter_map(|source| {
+				println!("cargo:rerun-if-changed={}", source.display());
+				self.compile_file(source)
+			})
+			.collect()
+	}
+
+	fn compile_file(&self, source: &Path) -> Option<PathBuf> {
+		let mut command = Command::new(&self.compiler);
+		command.arg("-o").arg(&self.out_dir);
+		for include in &self.include_dirs {
+			command.arg("-i").arg(include);
+		}
+		command.args(&self.args).arg(source);
+
+		let output = match command.output() {
+			Ok(output) => output,
+			Err(e) => {
+				warn(format!(
+					"couldn't run {} for {}: {e}",
+					self.compiler.display(),
+					source.display()
+				));
+				return None;
+			}
+		};
+		if !output.status.success() {
+			warn(format!("{} failed to compile:", source.display()));
+			for line in String::from_utf8_lossy(&output.stdout)
+				.lines()
+				.chain(String::from_utf8_lossy(&output.stderr).lines())
+				.filter(|line| !line.trim().is_empty())
+			{
+				warn(line);
+			}
+			return None;
+		}
+
+		// skshaderc keeps the .hlsl, so water.hlsl becomes water.hlsl.sks
+		let mut file_name = source.file_name()?.to_os_string();
+		file_name.push(".sks");
+		Some(self.out_dir.join(file_name))
+	}
+}
+
+fn warn(message: impl AsRef<str>) {
+	println!("cargo:warning={}", message.as_ref());
+}
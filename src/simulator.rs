@@ -0,0 +1,169 @@
+use crate::{HandJoint, Handed, Pose, StereoKitMultiThread};
+use glam::{Mat3, Mat4, Quat, Vec3};
+
+/// Common hand shapes for driving StereoKit's hands from code. These are
+/// handy for automated tests, since an overridden hand gets run through the
+/// same gesture detection as a real one, so a `Pinch` will press UI buttons!
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HandShape {
+	/// A relaxed, flat hand.
+	Open,
+	/// Index finger extended, everything else curled up.
+	Point,
+	/// Index finger and thumb tips touching.
+	Pinch,
+	/// All fingers curled into the palm.
+	Fist,
+}
+
+// Finger layout for a right hand, in meters, with the fingers pointing down
+// -Z and the back of the hand facing +Y. Left hands are mirrored along X.
+const ROOTS: [[f32; 3]; 5] = [
+	[-0.020, -0.010, 0.035],
+	[-0.015, 0.000, 0.040],
+	[-0.005, 0.000, 0.040],
+	[0.005, 0.000, 0.040],
+	[0.015, 0.000, 0.038],
+];
+const KNUCKLES: [[f32; 3]; 5] = [
+	[-0.035, -0.015, 0.015],
+	[-0.025, 0.000, -0.040],
+	[-0.005, 0.000, -0.042],
+	[0.013, 0.000, -0.038],
+	[0.030, 0.000, -0.030],
+];
+const SEGMENTS: [[f32; 3]; 5] = [
+	[0.035, 0.030, 0.025],
+	[0.040, 0.025, 0.020],
+	[0.045, 0.028, 0.022],
+	[0.042, 0.026, 0.021],
+	[0.033, 0.020, 0.018],
+];
+const RADII: [f32; 5] = [0.011, 0.010, 0.009, 0.008, 0.007];
+
+const OPEN: [f32; 3] = [0.10, 0.10, 0.05];
+const CURLED: [f32; 3] = [1.50, 1.60, 1.00];
+const THUMB_TUCKED: [f32; 3] = [0.50, 0.60, 0.40];
+
+impl HandShape {
+	/// How much each of the 3 bending joints of each finger curls, in radians.
+	fn curls(&self) -> [[f32; 3]; 5] {
+		match self {
+			HandShape::Open => [OPEN; 5],
+			HandShape::Point => [THUMB_TUCKED, OPEN, CURLED, CURLED, CURLED],
+			HandShape::Pinch => [
+				[0.2, 0.3, 0.2],
+				[0.6, 0.7, 0.4],
+				[0.3, 0.3, 0.2],
+				[0.3, 0.3, 0.2],
+				[0.3, 0.3, 0.2],
+			],
+			HandShape::Fist => [THUMB_TUCKED, CURLED, CURLED, CURLED, CURLED],
+		}
+	}
+
+	/// Builds the full set of joints for this shape, in the same finger/joint
+	/// layout as [`crate::Hand::fingers`]. The pose is the center of the palm,
+	/// with Forward pointing towards the fingertips and Up pointing out the
+	/// back of the hand.
+	pub fn joints(&self, hand: Handed, pose: Pose) -> [[HandJoint; 5]; 5] {
+		let side = if hand == Handed::Left { -1.0 } else { 1.0 };
+		let mirror = |v: [f32; 3]| Vec3::new(v[0] * side, v[1], v[2]);
+		let curls = self.curls();
+
+		let mut points = [[Vec3::ZERO; 5]; 5];
+		for finger in 0..5 {
+			let root = mirror(ROOTS[finger]);
+			let knuckle = mirror(KNUCKLES[finger]);
+			// Fingers curl towards the palm, the thumb curls across it
+			let towards = match finger {
+				0 => Vec3::new(side, -0.5, 0.0).normalize(),
+				_ => Vec3::NEG_Y,
+			};
+			let mut dir = match finger {
+				0 => Vec3::new(-0.6 * side, 0.0, -0.8).normalize(),
+				_ => Vec3::NEG_Z,
+			};
+			let axis = dir.cross(towards).normalize();
+
+			points[finger][0] = root;
+			points[finger][1] = knuckle;
+			for joint in 0..3 {
+				dir = Quat::from_axis_angle(axis, curls[finger][joint]) * dir;
+				points[finger][joint + 2] =
+					points[finger][joint + 1] + dir * SEGMENTS[finger][joint];
+			}
+		}
+
+		if *self == HandShape::Pinch {
+			// Bend the thumb the rest of the way so its tip meets the index tip
+			let offset = points[1][4] - points[0][4];
+			for joint in 1..5 {
+				points[0][joint] += offset * (joint as f32 / 4.0);
+			}
+		}
+
+		let up = pose.orientation * Vec3::Y;
+		let to_world = Mat4::from_rotation_translation(pose.orientation, pose.position);
+		points.map(|finger| {
+			let finger = finger.map(|point| to_world.transform_point3(point));
+			let mut joints = [HandJoint {
+				position: Vec3::ZERO,
+				orientation: Quat::IDENTITY,
+				radius: 0.0,
+			}; 5];
+			for joint in 0..5 {
+				let forward = match joint {
+					4 => finger[4] - finger[3],
+					_ => finger[joint + 1] - finger[joint],
+				};
+				joints[joint] = HandJoint {
+					position: finger[joint],
+					orientation: look_dir(forward, up),
+					radius: RADII[joint],
+				};
+			}
+			joints
+		})
+	}
+}
+
+/// An orientation where Forward (-Z) points along `forward`, and Up is as
+/// close to `up` as it can be.
+fn look_dir(forward: Vec3, up: Vec3) -> Quat {
+	let back = -forward.normalize_or_zero();
+	if back == Vec3::ZERO {
+		return Quat::IDENTITY;
+	}
+	let right = up.cross(back).normalize_or_zero();
+	if right == Vec3::ZERO {
+		return Quat::from_rotation_arc(Vec3::NEG_Z, -back);
+	}
+	let up = back.cross(right);
+	Quat::from_mat3(&Mat3::from_cols(right, up, back))
+}
+
+/// Overrides a hand with one of the [`HandShape`] presets, placed at `pose`.
+/// The hand stays there until you call this again, or
+/// [`StereoKitMultiThread::input_hand_override_clear`].
+pub fn simulator_hand_pose(
+	sk: &impl StereoKitMultiThread,
+	hand: Handed,
+	shape: HandShape,
+	pose: impl Into<Pose>,
+) {
+	sk.input_hand_override(hand, &shape.joints(hand, pose.into()));
+}
+
+/// Moves the simulated head to the given world space pose by shifting the
+/// camera root, so it works on flatscreen where there's no headset to move.
+/// Other things attached to the camera root will move along with it!
+pub fn simulator_head_pose(sk: &impl StereoKitMultiThread, head: impl Into<Pose>) {
+	let head = head.into();
+	let root = sk.render_get_cam_root();
+	let current = sk.input_head();
+	let current = Mat4::from_rotation_translation(current.orientation, current.position);
+	let local = root.inverse() * current;
+	let target = Mat4::from_rotation_translation(head.orientation, head.position);
+	sk.render_set_cam_root(target * local.inverse());
+}
@@ -0,0 +1,404 @@
+use crate::{
+	AnimMode, Color32, SkResult, Sprite, SpriteType, StereoKitDraw, StereoKitError,
+	StereoKitMultiThread, Tex, TextAlign, TextureType,
+};
+use glam::Mat4;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// A rectangle of pixels on a texture, measured from the top left. The field
+/// names match the `frame` objects in a TexturePacker style JSON atlas.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpriteRect {
+	pub x: u32,
+	pub y: u32,
+	#[serde(rename = "w")]
+	pub width: u32,
+	#[serde(rename = "h")]
+	pub height: u32,
+}
+
+impl SpriteRect {
+	pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+		Self {
+			x,
+			y,
+			width,
+			height,
+		}
+	}
+}
+
+#[derive(Deserialize)]
+struct AtlasFrame {
+	frame: SpriteRect,
+}
+
+#[derive(Deserialize)]
+struct AtlasNamedFrame {
+	filename: String,
+	frame: SpriteRect,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AtlasFrames {
+	Hash(BTreeMap<String, AtlasFrame>),
+	Array(Vec<AtlasNamedFrame>),
+}
+
+#[derive(Deserialize)]
+struct Atlas {
+	frames: AtlasFrames,
+}
+
+/// A texture cut up into Sprites! StereoKit Sprites always show a whole
+/// texture, so each region gets copied out into a texture of its own. With
+/// `SpriteType::Atlased` they all get packed back onto an atlas, so drawing
+/// them stays cheap.
+pub struct SpriteSheet {
+	sprites: Vec<Sprite>,
+	rects: Vec<SpriteRect>,
+	names: HashMap<String, usize>,
+}
+
+impl SpriteSheet {
+	/// Cuts `tex` into a grid of equally sized cells, left to right and then
+	/// top to bottom. The texture's pixels get read back from the GPU, so it
+	/// needs to have finished loading first.
+	pub fn from_grid<S: AsRef<str>>(
+		sk: &impl StereoKitMultiThread,
+		tex: impl AsRef<Tex>,
+		columns: u32,
+		rows: u32,
+		sprite_type: SpriteType,
+		atlas_id: S,
+	) -> SkResult<Self> {
+		let tex = tex.as_ref();
+		if columns == 0 || rows == 0 {
+			return Err(StereoKitError::SpriteSheet(
+				"a grid needs at least one row and column".to_string(),
+			));
+		}
+		let rects = grid_rects(
+			sk.tex_get_width(tex) as u32,
+			sk.tex_get_height(tex) as u32,
+			columns,
+			rows,
+		)
+		.map(|rect| (None, rect));
+		Self::slice(sk, tex, rects, sprite_type, atlas_id)
+	}
+
+	/// Cuts `tex` up following a JSON atlas, in TexturePacker's "hash" or
+	/// "array" format. Each frame is named after its key or `filename`, and
+	/// frames from a hash are ordered by name.
+	pub fn from_atlas_json<S: AsRef<str>>(
+		sk: &impl StereoKitMultiThread,
+		tex: impl AsRef<Tex>,
+		json: &str,
+		sprite_type: SpriteType,
+		atlas_id: S,
+	) -> SkResult<Self> {
+		let atlas: Atlas =
+			serde_json::from_str(json).map_err(|e| StereoKitError::SpriteSheet(e.to_string()))?;
+		let frames: Vec<(String, SpriteRect)> = match atlas.frames {
+			AtlasFrames::Hash(frames) => frames
+				.into_iter()
+				.map(|(name, frame)| (name, frame.frame))
+				.collect(),
+			AtlasFrames::Array(frames) => frames
+				.into_iter()
+				.map(|frame| (frame.filename, frame.frame))
+				.collect(),
+		};
+		let rects = frames.into_iter().map(|(name, rect)| (Some(name), rect));
+		Self::slice(sk, tex, rects, sprite_type, atlas_id)
+	}
+
+	/// Cuts out each rectangle of `tex`, in order.
+	pub fn from_rects<S: AsRef<str>>(
+		sk: &impl StereoKitMultiThread,
+		tex: impl AsRef<Tex>,
+		rects: &[SpriteRect],
+		sprite_type: SpriteType,
+		atlas_id: S,
+	) -> SkResult<Self> {
+		let rects = rects.iter().map(|rect| (None, *rect));
+		Self::slice(sk, tex, rects, sprite_type, atlas_id)
+	}
+
+	fn slice<S: AsRef<str>>(
+		sk: &impl StereoKitMultiThread,
+		tex: impl AsRef<Tex>,
+		rects: impl Iterator<Item = (Option<String>, SpriteRect)>,
+		sprite_type: SpriteType,
+		atlas_id: S,
+	) -> SkResult<Self> {
+		let tex = tex.as_ref();
+		let tex_width = sk.tex_get_width(tex) as u32;
+		let tex_height = sk.tex_get_height(tex) as u32;
+		let format = sk.tex_get_format(tex);
+		let pixel_size = format.size();
+		if pixel_size == 0 {
+			return Err(StereoKitError::SpriteSheet(format!(
+				"can't slice a texture with format {format:?}"
+			)));
+		}
+		let data = sk.tex_get_data(tex);
+
+		let mut sheet = Self {
+			sprites: Vec::new(),
+			rects: Vec::new(),
+			names: HashMap::new(),
+		};
+		for (name, rect) in rects {
+			let pixels = cut_rect(&data, tex_width, tex_height, pixel_size, rect)?;
+			// cut_rect made sure the rect is on the texture, so this fits in an i32, and
+			// pixels is exactly the size tex_set_colors wants
+			let frame = sk.tex_create(TextureType::IMAGE_NO_MIPS, format);
+			sk.tex_set_colors(&frame, rect.width as i32, rect.height as i32, &pixels);
+			let sprite = sk.sprite_create(&frame, sprite_type, atlas_id.as_ref());
+			// The sprite holds its own reference, if it was made
+			unsafe { sk.tex_release(frame) };
+			let sprite = sprite?;
+			if let Some(name) = name {
+				sheet.names.insert(name, sheet.sprites.len());
+			}
+			sheet.sprites.push(sprite);
+			sheet.rects.push(rect);
+		}
+		Ok(sheet)
+	}
+
+	pub fn len(&self) -> usize {
+		self.sprites.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.sprites.is_empty()
+	}
+	pub fn get(&self, index: usize) -> Option<&Sprite> {
+		self.sprites.get(index)
+	}
+	/// Where on the original texture this sprite came from.
+	pub fn rect(&self, index: usize) -> Option<SpriteRect> {
+		self.rects.get(index).copied()
+	}
+	/// The index of a frame from a JSON atlas, by name.
+	pub fn index_of(&self, name: &str) -> Option<usize> {
+		self.names.get(name).copied()
+	}
+	pub fn by_name(&self, name: &str) -> Option<&Sprite> {
+		self.index_of(name).and_then(|index| self.get(index))
+	}
+	/// The indices of every named frame starting with `prefix`, ordered by
+	/// name. Atlas tools usually name animation frames like `walk_00`,
+	/// `walk_01`, so this gets a whole animation at once.
+	pub fn indices_with_prefix(&self, prefix: &str) -> Vec<usize> {
+		let mut named: Vec<(&String, usize)> = self
+			.names
+			.iter()
+			.filter(|(name, _)| name.starts_with(prefix))
+			.map(|(name, index)| (name, *index))
+			.collect();
+		named.sort();
+		named.into_iter().map(|(_, index)| index).collect()
+	}
+	pub fn iter(&self) -> impl Iterator<Item = &Sprite> {
+		self.sprites.iter()
+	}
+}
+
+/// The cells of a grid over a texture, left to right and then top to bottom.
+fn grid_rects(
+	tex_width: u32,
+	tex_height: u32,
+	columns: u32,
+	rows: u32,
+) -> impl Iterator<Item = SpriteRect> {
+	let width = tex_width / columns;
+	let height = tex_height / rows;
+	(0..rows).flat_map(move |row| {
+		(0..columns).map(move |column| SpriteRect::new(column * width, row * height, width, height))
+	})
+}
+
+/// Copies the pixels under `rect` out of a texture's data.
+fn cut_rect(
+	data: &[u8],
+	tex_width: u32,
+	tex_height: u32,
+	pixel_size: usize,
+	rect: SpriteRect,
+) -> SkResult<Vec<u8>> {
+	// Atlas files aren't trusted, so these can't be allowed to overflow
+	let right = rect.x.checked_add(rect.width);
+	let bottom = rect.y.checked_add(rect.height);
+	if rect.width == 0
+		|| rect.height == 0
+		|| right.map_or(true, |right| right > tex_width)
+		|| bottom.map_or(true, |bottom| bottom > tex_height)
+	{
+		return Err(StereoKitError::SpriteSheet(format!(
+			"{rect:?} doesn't fit on a {tex_width}x{tex_height} texture"
+		)));
+	}
+	let row_size = rect.width as usize * pixel_size;
+	if data.len() < tex_width as usize * tex_height as usize * pixel_size {
+		return Err(StereoKitError::SpriteSheet(format!(
+			"{} bytes of pixels don't cover a {tex_width}x{tex_height} texture, has it finished loading?",
+			data.len()
+		)));
+	}
+	let mut pixels = Vec::with_capacity(row_size * rect.height as usize);
+	for y in rect.y..rect.y + rect.height {
+		let start = (y as usize * tex_width as usize + rect.x as usize) * pixel_size;
+		pixels.extend_from_slice(&data[start..start + row_size]);
+	}
+	Ok(pixels)
+}
+
+/// Flips through frames of a [`SpriteSheet`] over time. Call
+/// [`AnimatedSprite::step`] once a frame to move it along with `time_step`.
+/// `AnimMode::Manual` animations never move on their own, set their time
+/// instead.
+#[derive(Debug, Clone)]
+pub struct AnimatedSprite {
+	/// Indices into the sprite sheet, in the order they play.
+	pub frames: Vec<usize>,
+	/// Frames per second.
+	pub fps: f32,
+	pub mode: AnimMode,
+	pub playing: bool,
+	time: f32,
+}
+
+impl AnimatedSprite {
+	pub fn new(frames: impl Into<Vec<usize>>, fps: f32, mode: AnimMode) -> Self {
+		Self {
+			frames: frames.into(),
+			fps,
+			mode,
+			playing: true,
+			time: 0.0,
+		}
+	}
+
+	/// Plays every frame of the sheet, in order.
+	pub fn from_sheet(sheet: &SpriteSheet, fps: f32, mode: AnimMode) -> Self {
+		Self::new((0..sheet.len()).collect::<Vec<_>>(), fps, mode)
+	}
+
+	pub fn step(&mut self, sk: &impl StereoKitMultiThread) {
+		if self.playing && self.mode != AnimMode::Manual {
+			self.time += sk.time_step_f32();
+		}
+	}
+
+	pub fn play(&mut self) {
+		self.playing = true;
+	}
+	pub fn pause(&mut self) {
+		self.playing = false;
+	}
+	/// Back to the first frame, and playing.
+	pub fn restart(&mut self) {
+		self.time = 0.0;
+		self.playing = true;
+	}
+
+	/// Seconds since the animation started.
+	pub fn time(&self) -> f32 {
+		self.time
+	}
+	pub fn set_time(&mut self, time: f32) {
+		self.time = time.max(0.0);
+	}
+	/// Seconds for one pass through all the frames.
+	pub fn duration(&self) -> f32 {
+		if self.fps > 0.0 {
+			self.frames.len() as f32 / self.fps
+		} else {
+			0.0
+		}
+	}
+	/// A `AnimMode::Once` animation that's reached its last frame.
+	pub fn is_finished(&self) -> bool {
+		self.mode == AnimMode::Once && self.time >= self.duration()
+	}
+
+	/// Which frame of the animation is showing, not the sprite sheet index.
+	pub fn frame(&self) -> usize {
+		let count = self.frames.len();
+		if count == 0 {
+			return 0;
+		}
+		let frame = (self.time * self.fps).max(0.0) as usize;
+		match self.mode {
+			AnimMode::Once => frame.min(count - 1),
+			AnimMode::Loop | AnimMode::Manual => frame % count,
+		}
+	}
+
+	/// The sprite for the frame that's showing.
+	pub fn current<'a>(&self, sheet: &'a SpriteSheet) -> Option<&'a Sprite> {
+		self.frames
+			.get(self.frame())
+			.and_then(|index| sheet.get(*index))
+	}
+
+	pub fn draw(
+		&self,
+		sk: &impl StereoKitDraw,
+		sheet: &SpriteSheet,
+		transform: impl Into<Mat4>,
+		color: Color32,
+	) {
+		if let Some(sprite) = self.current(sheet) {
+			sk.sprite_draw(sprite, transform, color);
+		}
+	}
+
+	pub fn draw_at(
+		&self,
+		sk: &impl StereoKitDraw,
+		sheet: &SpriteSheet,
+		transform: impl Into<Mat4>,
+		anchor_position: TextAlign,
+		color: Color32,
+	) {
+		if let Some(sprite) = self.current(sheet) {
+			sk.sprite_draw_at(sprite, transform, anchor_position, color);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn grid_cells() {
+		let rects: Vec<SpriteRect> = grid_rects(100, 50, 4, 2).collect();
+		assert_eq!(rects.len(), 8);
+		assert_eq!(rects[0], SpriteRect::new(0, 0, 25, 25));
+		assert_eq!(rects[4], SpriteRect::new(0, 25, 25, 25));
+		assert_eq!(rects[7], SpriteRect::new(75, 25, 25, 25));
+	}
+
+	#[test]
+	fn cuts_rects() {
+		// A 4x2 texture, one byte a pixel
+		let data: Vec<u8> = (0..8).collect();
+		let pixels = cut_rect(&data, 4, 2, 1, SpriteRect::new(1, 0, 2, 2)).unwrap();
+		assert_eq!(pixels, [1, 2, 5, 6]);
+		let pixels = cut_rect(&data, 2, 1, 4, SpriteRect::new(1, 0, 1, 1)).unwrap();
+		assert_eq!(pixels, [4, 5, 6, 7]);
+
+		assert!(cut_rect(&data, 4, 2, 1, SpriteRect::new(3, 0, 2, 1)).is_err());
+		assert!(cut_rect(&data, 4, 2, 1, SpriteRect::new(0, 0, 0, 1)).is_err());
+		assert!(cut_rect(&data, 4, 2, 1, SpriteRect::new(u32::MAX, 0, 2, 1)).is_err());
+		// Pixels that couldn't be read back
+		assert!(cut_rect(&[], 4, 2, 1, SpriteRect::new(0, 0, 1, 1)).is_err());
+	}
+}
@@ -0,0 +1,365 @@
+use crate::StereoKitMultiThread;
+
+/// A clock that can be paused and sped up on its own, without touching
+/// StereoKit's global `time_scale`. It moves forward by `time_step`, so
+/// `time_scale` still applies on top of its own `scale`.
+#[derive(Debug, Clone)]
+pub struct Clock {
+	/// Multiplies how fast this clock runs, on top of `time_scale`.
+	pub scale: f64,
+	paused: bool,
+	time: f64,
+	delta: f64,
+}
+
+impl Default for Clock {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Clock {
+	pub fn new() -> Self {
+		Self {
+			scale: 1.0,
+			paused: false,
+			time: 0.0,
+			delta: 0.0,
+		}
+	}
+
+	/// Moves the clock forward by this frame's `time_step`, call once a frame.
+	/// Returns how far it moved.
+	pub fn step(&mut self, sk: &impl StereoKitMultiThread) -> f64 {
+		self.advance(sk.time_step())
+	}
+
+	/// Moves the clock forward by `seconds`, before scaling and pausing.
+	pub fn advance(&mut self, seconds: f64) -> f64 {
+		self.delta = if self.paused {
+			0.0
+		} else {
+			seconds * self.scale
+		};
+		self.time += self.delta;
+		self.delta
+	}
+
+	pub fn pause(&mut self) {
+		self.paused = true;
+	}
+	pub fn resume(&mut self) {
+		self.paused = false;
+	}
+	pub fn is_paused(&self) -> bool {
+		self.paused
+	}
+	/// Seconds this clock has run for.
+	pub fn time(&self) -> f64 {
+		self.time
+	}
+	pub fn set_time(&mut self, time: f64) {
+		self.time = time;
+	}
+	/// How far the clock moved on the last step.
+	pub fn delta(&self) -> f64 {
+		self.delta
+	}
+}
+
+/// Turns uneven frame times into even, fixed size steps, like physics and
+/// networking want. Leftover time carries over to the next frame, and
+/// [`FixedTimestep::alpha`] says how far between steps we are, for
+/// interpolating what gets drawn.
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+	/// Seconds per step.
+	pub step: f64,
+	/// The most steps to run in one frame. After a long hitch, the time that
+	/// doesn't fit is dropped rather than running hundreds of steps to catch up.
+	pub max_steps: u32,
+	accumulator: f64,
+	ticks: u64,
+}
+
+impl FixedTimestep {
+	pub fn new(step: f64) -> Self {
+		Self {
+			step,
+			max_steps: 8,
+			accumulator: 0.0,
+			ticks: 0,
+		}
+	}
+
+	/// Steps `rate` times a second.
+	pub fn from_rate(rate: f64) -> Self {
+		Self::new(1.0 / rate)
+	}
+
+	/// Adds `delta` seconds, and calls `tick` with the step size for each
+	/// whole step that's built up. Returns how many steps ran.
+	pub fn advance(&mut self, delta: f64, mut tick: impl FnMut(f64)) -> u32 {
+		if self.step <= 0.0 {
+			return 0;
+		}
+		self.accumulator += delta.max(0.0);
+		let mut steps = 0;
+		while self.accumulator >= self.step {
+			if steps == self.max_steps {
+				self.accumulator %= self.step;
+				break;
+			}
+			tick(self.step);
+			self.accumulator -= self.step;
+			self.ticks += 1;
+			steps += 1;
+		}
+		steps
+	}
+
+	/// How far we are towards the next step, from 0 to 1. Blend the last two
+	/// steps' results by this to draw smooth motion.
+	pub fn alpha(&self) -> f64 {
+		if self.step > 0.0 {
+			(self.accumulator / self.step).clamp(0.0, 1.0)
+		} else {
+			0.0
+		}
+	}
+
+	/// How many steps have run in total.
+	pub fn ticks(&self) -> u64 {
+		self.ticks
+	}
+}
+
+/// Identifies something added to a [`Scheduler`], for cancelling it later.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+struct FixedTask<C> {
+	id: TaskId,
+	timestep: FixedTimestep,
+	callback: Box<dyn FnMut(&mut C, f64)>,
+}
+
+struct Timer<C> {
+	id: TaskId,
+	remaining: f64,
+	interval: Option<f64>,
+	callback: Box<dyn FnMut(&mut C)>,
+}
+
+/// Fixed rate updates and timers, all running off one [`Clock`]! Callbacks
+/// get a `&mut C` each time they run, passed in to [`Scheduler::step`]. Keep
+/// the scheduler next to your state rather than inside it, like so:
+///
+/// ```ignore
+/// sk.run_stateful(
+///     &mut (scheduler, state),
+///     |(scheduler, state), sk, draw| {
+///         scheduler.step(sk, state);
+///     },
+///     |_, _| {},
+/// );
+/// ```
+pub struct Scheduler<C> {
+	pub clock: Clock,
+	/// The most times a repeating timer goes off in one frame, like
+	/// [`FixedTimestep::max_steps`]. Intervals missed past this are dropped.
+	pub max_steps: u32,
+	fixed: Vec<FixedTask<C>>,
+	timers: Vec<Timer<C>>,
+	next_id: u64,
+}
+
+impl<C> Default for Scheduler<C> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<C> Scheduler<C> {
+	pub fn new() -> Self {
+		Self {
+			clock: Clock::new(),
+			max_steps: 8,
+			fixed: Vec::new(),
+			timers: Vec::new(),
+			next_id: 0,
+		}
+	}
+
+	fn next_id(&mut self) -> TaskId {
+		self.next_id += 1;
+		TaskId(self.next_id)
+	}
+
+	/// Calls `callback` with the step size every `step` seconds of clock time.
+	pub fn add_fixed(&mut self, step: f64, callback: impl FnMut(&mut C, f64) + 'static) -> TaskId {
+		let id = self.next_id();
+		self.fixed.push(FixedTask {
+			id,
+			timestep: FixedTimestep::new(step),
+			callback: Box::new(callback),
+		});
+		id
+	}
+
+	/// Calls `callback` once, after `delay` seconds of clock time.
+	pub fn after(&mut self, delay: f64, callback: impl FnOnce(&mut C) + 'static) -> TaskId {
+		let id = self.next_id();
+		let mut callback = Some(callback);
+		self.timers.push(Timer {
+			id,
+			remaining: delay,
+			interval: None,
+			callback: Box::new(move |context| {
+				if let Some(callback) = callback.take() {
+					callback(context)
+				}
+			}),
+		});
+		id
+	}
+
+	/// Calls `callback` every `interval` seconds of clock time, until it's
+	/// cancelled. Panics if `interval` isn't above zero.
+	pub fn every(&mut self, interval: f64, callback: impl FnMut(&mut C) + 'static) -> TaskId {
+		assert!(
+			interval > 0.0,
+			"timer interval must be positive, got {interval}"
+		);
+		let id = self.next_id();
+		self.timers.push(Timer {
+			id,
+			remaining: interval,
+			interval: Some(interval),
+			callback: Box::new(callback),
+		});
+		id
+	}
+
+	/// Stops a fixed update or timer, returns false if it had already finished.
+	pub fn cancel(&mut self, id: TaskId) -> bool {
+		let count = self.fixed.len() + self.timers.len();
+		self.fixed.retain(|task| task.id != id);
+		self.timers.retain(|timer| timer.id != id);
+		count != self.fixed.len() + self.timers.len()
+	}
+
+	pub fn is_active(&self, id: TaskId) -> bool {
+		self.fixed.iter().any(|task| task.id == id)
+			|| self.timers.iter().any(|timer| timer.id == id)
+	}
+
+	/// The interpolation alpha of a fixed update, see [`FixedTimestep::alpha`].
+	pub fn alpha(&self, id: TaskId) -> Option<f64> {
+		self.fixed
+			.iter()
+			.find(|task| task.id == id)
+			.map(|task| task.timestep.alpha())
+	}
+
+	/// Seconds until a timer goes off next.
+	pub fn remaining(&self, id: TaskId) -> Option<f64> {
+		self.timers
+			.iter()
+			.find(|timer| timer.id == id)
+			.map(|timer| timer.remaining.max(0.0))
+	}
+
+	/// Steps the clock by this frame's `time_step`, then runs whatever is due.
+	/// Call this once a frame.
+	pub fn step(&mut self, sk: &impl StereoKitMultiThread, context: &mut C) {
+		let delta = self.clock.step(sk);
+		self.run(delta, context);
+	}
+
+	/// Runs whatever is due after `delta` more seconds of clock time, without
+	/// touching the clock.
+	pub fn run(&mut self, delta: f64, context: &mut C) {
+		for task in &mut self.fixed {
+			let callback = &mut task.callback;
+			task.timestep.advance(delta, |step| callback(context, step));
+		}
+
+		let max_steps = self.max_steps;
+		self.timers.retain_mut(|timer| {
+			timer.remaining -= delta;
+			let mut steps = 0;
+			while timer.remaining <= 0.0 {
+				let Some(interval) = timer.interval else {
+					(timer.callback)(context);
+					return false;
+				};
+				// Catches up on missed intervals, same as a fixed step would
+				if steps == max_steps {
+					timer.remaining = interval + timer.remaining % interval;
+					break;
+				}
+				(timer.callback)(context);
+				timer.remaining += interval;
+				steps += 1;
+			}
+			true
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fixed_timestep_accumulates() {
+		let mut timestep = FixedTimestep::new(0.1);
+		let mut ticked = 0.0;
+		assert_eq!(timestep.advance(0.25, |step| ticked += step), 2);
+		assert!((ticked - 0.2).abs() < 1e-9);
+		assert!((timestep.alpha() - 0.5).abs() < 1e-6);
+		// The leftover carries over into the next frame
+		assert_eq!(timestep.advance(0.06, |_| {}), 1);
+		assert!((timestep.alpha() - 0.1).abs() < 1e-6);
+		assert_eq!(timestep.ticks(), 3);
+		// Negative time doesn't rewind anything
+		assert_eq!(timestep.advance(-1.0, |_| {}), 0);
+		assert_eq!(timestep.ticks(), 3);
+	}
+
+	#[test]
+	fn fixed_timestep_drops_long_hitches() {
+		let mut timestep = FixedTimestep::new(0.1);
+		assert_eq!(timestep.advance(10.05, |_| {}), timestep.max_steps);
+		assert!((timestep.alpha() - 0.5).abs() < 1e-3);
+		assert_eq!(timestep.advance(0.0, |_| {}), 0);
+
+		let mut stopped = FixedTimestep::new(0.0);
+		assert_eq!(stopped.advance(1.0, |_| {}), 0);
+		assert_eq!(stopped.alpha(), 0.0);
+	}
+
+	#[test]
+	fn scheduler_caps_timer_catch_up() {
+		let mut scheduler = Scheduler::<u32>::new();
+		let timer = scheduler.every(0.1, |count| *count += 1);
+		let mut count = 0;
+		scheduler.run(10.05, &mut count);
+		assert_eq!(count, scheduler.max_steps);
+		let remaining = scheduler.remaining(timer).unwrap();
+		assert!(remaining > 0.0);
+		assert!(remaining <= 0.1);
+
+		let mut fired = 0;
+		scheduler.after(0.5, |count| *count += 10);
+		scheduler.run(0.5, &mut fired);
+		assert_eq!(fired, 15);
+	}
+
+	#[test]
+	#[should_panic]
+	fn scheduler_rejects_empty_intervals() {
+		Scheduler::<()>::new().every(0.0, |_| {});
+	}
+}
@@ -0,0 +1,604 @@
+use crate::{Color128, Material, Pose, Sk, StereoKitMultiThread};
+use glam::{Quat, Vec2, Vec3, Vec4};
+use std::cell::Cell;
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+/// Something that can be blended between two values.
+pub trait Tweenable: Copy + 'static {
+	fn tween(from: Self, to: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+	fn tween(from: Self, to: Self, t: f32) -> Self {
+		from + (to - from) * t
+	}
+}
+impl Tweenable for Vec2 {
+	fn tween(from: Self, to: Self, t: f32) -> Self {
+		from.lerp(to, t)
+	}
+}
+impl Tweenable for Vec3 {
+	fn tween(from: Self, to: Self, t: f32) -> Self {
+		from.lerp(to, t)
+	}
+}
+impl Tweenable for Vec4 {
+	fn tween(from: Self, to: Self, t: f32) -> Self {
+		from.lerp(to, t)
+	}
+}
+impl Tweenable for Quat {
+	fn tween(from: Self, to: Self, t: f32) -> Self {
+		from.slerp(to, t)
+	}
+}
+impl Tweenable for Pose {
+	fn tween(from: Self, to: Self, t: f32) -> Self {
+		Pose::new(
+			from.position.lerp(to.position, t),
+			from.orientation.slerp(to.orientation, t),
+		)
+	}
+}
+impl Tweenable for Color128 {
+	fn tween(from: Self, to: Self, t: f32) -> Self {
+		Color128::new(
+			f32::tween(from.r, to.r, t),
+			f32::tween(from.g, to.g, t),
+			f32::tween(from.b, to.b, t),
+			f32::tween(from.a, to.a, t),
+		)
+	}
+}
+
+/// The standard easing curves, see <https://easings.net> for what they look like.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Ease {
+	Linear,
+	InQuad,
+	OutQuad,
+	InOutQuad,
+	InCubic,
+	OutCubic,
+	InOutCubic,
+	InQuart,
+	OutQuart,
+	InOutQuart,
+	InSine,
+	OutSine,
+	InOutSine,
+	InExpo,
+	OutExpo,
+	InOutExpo,
+	InBack,
+	OutBack,
+	InOutBack,
+	InElastic,
+	OutElastic,
+	InOutElastic,
+	InBounce,
+	OutBounce,
+	InOutBounce,
+}
+
+impl Ease {
+	/// Maps progress from 0 to 1 onto the curve. Back and Elastic go a little
+	/// past 0 and 1 on purpose.
+	pub fn apply(self, t: f32) -> f32 {
+		let t = t.clamp(0.0, 1.0);
+		// Builds an InOut curve out of an In curve
+		let in_out = |ease_in: fn(f32) -> f32| {
+			if t < 0.5 {
+				ease_in(t * 2.0) * 0.5
+			} else {
+				1.0 - ease_in((1.0 - t) * 2.0) * 0.5
+			}
+		};
+		let out = |ease_in: fn(f32) -> f32| 1.0 - ease_in(1.0 - t);
+		match self {
+			Ease::Linear => t,
+			Ease::InQuad => in_quad(t),
+			Ease::OutQuad => out(in_quad),
+			Ease::InOutQuad => in_out(in_quad),
+			Ease::InCubic => in_cubic(t),
+			Ease::OutCubic => out(in_cubic),
+			Ease::InOutCubic => in_out(in_cubic),
+			Ease::InQuart => in_quart(t),
+			Ease::OutQuart => out(in_quart),
+			Ease::InOutQuart => in_out(in_quart),
+			Ease::InSine => in_sine(t),
+			Ease::OutSine => out(in_sine),
+			Ease::InOutSine => in_out(in_sine),
+			Ease::InExpo => in_expo(t),
+			Ease::OutExpo => out(in_expo),
+			Ease::InOutExpo => in_out(in_expo),
+			Ease::InBack => in_back(t),
+			Ease::OutBack => out(in_back),
+			Ease::InOutBack => in_out(in_back),
+			Ease::InElastic => in_elastic(t),
+			Ease::OutElastic => out(in_elastic),
+			Ease::InOutElastic => in_out(in_elastic),
+			Ease::InBounce => out(out_bounce),
+			Ease::OutBounce => out_bounce(t),
+			Ease::InOutBounce => in_out(|t| 1.0 - out_bounce(1.0 - t)),
+		}
+	}
+}
+
+fn in_quad(t: f32) -> f32 {
+	t * t
+}
+fn in_cubic(t: f32) -> f32 {
+	t * t * t
+}
+fn in_quart(t: f32) -> f32 {
+	t * t * t * t
+}
+fn in_sine(t: f32) -> f32 {
+	1.0 - (t * PI * 0.5).cos()
+}
+fn in_expo(t: f32) -> f32 {
+	if t <= 0.0 {
+		0.0
+	} else {
+		2f32.powf(10.0 * t - 10.0)
+	}
+}
+fn in_back(t: f32) -> f32 {
+	let overshoot = 1.70158;
+	t * t * ((overshoot + 1.0) * t - overshoot)
+}
+fn in_elastic(t: f32) -> f32 {
+	if t <= 0.0 || t >= 1.0 {
+		return t;
+	}
+	-(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+}
+fn out_bounce(t: f32) -> f32 {
+	let (n, d) = (7.5625, 2.75);
+	if t < 1.0 / d {
+		n * t * t
+	} else if t < 2.0 / d {
+		let t = t - 1.5 / d;
+		n * t * t + 0.75
+	} else if t < 2.5 / d {
+		let t = t - 2.25 / d;
+		n * t * t + 0.9375
+	} else {
+		let t = t - 2.625 / d;
+		n * t * t + 0.984375
+	}
+}
+
+/// What a tween does once it reaches the end.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Repeat {
+	Once,
+	/// Plays from the start again, this many times in total.
+	Loop(u32),
+	LoopForever,
+	/// Plays forward then backward, this many round trips.
+	Yoyo(u32),
+	YoyoForever,
+}
+
+/// A value that tweens can animate, shared between you and the [`Tweener`].
+/// Read it with `get` whenever you need it, like when drawing.
+#[derive(Debug, Default)]
+pub struct Tweened<T: Copy>(Rc<Cell<T>>);
+
+impl<T: Copy> Clone for Tweened<T> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+}
+
+impl<T: Copy> Tweened<T> {
+	pub fn new(value: T) -> Self {
+		Self(Rc::new(Cell::new(value)))
+	}
+	pub fn get(&self) -> T {
+		self.0.get()
+	}
+	pub fn set(&self, value: T) {
+		self.0.set(value)
+	}
+}
+
+/// Something a tween plays, that can jump to any point in its timeline.
+trait Track {
+	fn duration(&self) -> f32;
+	fn seek(&mut self, sk: &Sk, time: f32);
+}
+
+struct ValueTrack<T: Tweenable> {
+	get: Box<dyn Fn(&Sk) -> T>,
+	set: Box<dyn FnMut(&Sk, T)>,
+	from: Option<T>,
+	to: T,
+	duration: f32,
+	ease: Ease,
+}
+
+impl<T: Tweenable> Track for ValueTrack<T> {
+	fn duration(&self) -> f32 {
+		self.duration
+	}
+	fn seek(&mut self, sk: &Sk, time: f32) {
+		// Tweens without a starting value start from wherever the value is
+		// when they first play, which matters when they're in a sequence
+		let from = *self.from.get_or_insert_with(|| (self.get)(sk));
+		let progress = if self.duration > 0.0 {
+			time / self.duration
+		} else {
+			1.0
+		};
+		(self.set)(sk, T::tween(from, self.to, self.ease.apply(progress)));
+	}
+}
+
+struct WaitTrack(f32);
+
+impl Track for WaitTrack {
+	fn duration(&self) -> f32 {
+		self.0
+	}
+	fn seek(&mut self, _sk: &Sk, _time: f32) {}
+}
+
+struct SequenceTrack(Vec<Tween>);
+
+impl Track for SequenceTrack {
+	fn duration(&self) -> f32 {
+		self.0.iter().map(|tween| tween.duration()).sum()
+	}
+	fn seek(&mut self, sk: &Sk, time: f32) {
+		let mut starts = Vec::with_capacity(self.0.len());
+		let mut start = 0.0;
+		for tween in &self.0 {
+			starts.push(start);
+			start += tween.duration();
+		}
+		// Tweens that haven't been reached yet go back to their start first,
+		// latest first, so a loop or yoyo doesn't leave them on their last
+		// frame, and so the tween that's playing gets the final say
+		for (tween, start) in self.0.iter_mut().zip(&starts).rev() {
+			if time < *start {
+				tween.rewind(sk);
+			}
+		}
+		for (tween, start) in self.0.iter_mut().zip(&starts) {
+			if time >= *start {
+				let duration = tween.duration();
+				tween.seek(sk, (time - start).min(duration));
+			}
+		}
+	}
+}
+
+struct ParallelTrack(Vec<Tween>);
+
+impl Track for ParallelTrack {
+	fn duration(&self) -> f32 {
+		self.0
+			.iter()
+			.map(|tween| tween.duration())
+			.fold(0.0, f32::max)
+	}
+	fn seek(&mut self, sk: &Sk, time: f32) {
+		for tween in &mut self.0 {
+			let duration = tween.duration();
+			tween.seek(sk, time.min(duration));
+		}
+	}
+}
+
+/// An animation from one value to another over time, or a group of them!
+/// Hand it to a [`Tweener`] to play it.
+pub struct Tween {
+	track: Box<dyn Track>,
+	pub repeat: Repeat,
+	/// Seconds to wait before starting.
+	pub delay: f32,
+	on_complete: Option<Box<dyn FnMut()>>,
+	started: bool,
+	completed: bool,
+}
+
+impl Tween {
+	fn from_track(track: impl Track + 'static) -> Self {
+		Self {
+			track: Box::new(track),
+			repeat: Repeat::Once,
+			delay: 0.0,
+			on_complete: None,
+			started: false,
+			completed: false,
+		}
+	}
+
+	/// Tweens from one value to another, handing each step to `set`.
+	pub fn value<T: Tweenable>(
+		from: T,
+		to: T,
+		duration: f32,
+		ease: Ease,
+		mut set: impl FnMut(T) + 'static,
+	) -> Self {
+		Self::from_track(ValueTrack {
+			get: Box::new(move |_| from),
+			set: Box::new(move |_, value| set(value)),
+			from: Some(from),
+			to,
+			duration,
+			ease,
+		})
+	}
+
+	/// Tweens a shared value from wherever it is when the tween starts, to `to`.
+	pub fn to<T: Tweenable>(target: &Tweened<T>, to: T, duration: f32, ease: Ease) -> Self {
+		let get = target.clone();
+		let set = target.clone();
+		Self::from_track(ValueTrack {
+			get: Box::new(move |_| get.get()),
+			set: Box::new(move |_, value| set.set(value)),
+			from: None,
+			to,
+			duration,
+			ease,
+		})
+	}
+
+	/// Tweens a shared value between two values.
+	pub fn from_to<T: Tweenable>(
+		target: &Tweened<T>,
+		from: T,
+		to: T,
+		duration: f32,
+		ease: Ease,
+	) -> Self {
+		let set = target.clone();
+		Self::value(from, to, duration, ease, move |value| set.set(value))
+	}
+
+	/// Tweens a float parameter on a Material, from wherever it is when the
+	/// tween starts, to `to`.
+	pub fn material_float(
+		sk: &impl StereoKitMultiThread,
+		material: &Material,
+		name: impl Into<String>,
+		to: f32,
+		duration: f32,
+		ease: Ease,
+	) -> Self {
+		// The tween keeps its own reference to the Material
+		unsafe { sk.material_addref(material) };
+		let material = Rc::new(Material(material.0));
+		let name = name.into();
+		let (get_material, get_name) = (material.clone(), name.clone());
+		Self::from_track(ValueTrack {
+			get: Box::new(move |sk| sk.material_get_float(&*get_material, &get_name)),
+			set: Box::new(move |sk, value| sk.material_set_float(&*material, &name, value)),
+			from: None,
+			to,
+			duration,
+			ease,
+		})
+	}
+
+	/// Does nothing for a while, for spacing out a sequence.
+	pub fn wait(duration: f32) -> Self {
+		Self::from_track(WaitTrack(duration))
+	}
+
+	/// Plays each tween after the one before it finishes.
+	pub fn sequence(tweens: impl IntoIterator<Item = Tween>) -> Self {
+		Self::from_track(SequenceTrack(tweens.into_iter().collect()))
+	}
+
+	/// Plays all the tweens at once, finishing when the longest one does.
+	pub fn parallel(tweens: impl IntoIterator<Item = Tween>) -> Self {
+		Self::from_track(ParallelTrack(tweens.into_iter().collect()))
+	}
+
+	pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+		self.repeat = repeat;
+		self
+	}
+
+	pub fn with_delay(mut self, delay: f32) -> Self {
+		self.delay = delay;
+		self
+	}
+
+	/// Called when the tween finishes, after its delay and all its repeats.
+	/// In a yoyo or looping group, that can happen more than once.
+	pub fn on_complete(mut self, on_complete: impl FnMut() + 'static) -> Self {
+		self.on_complete = Some(Box::new(on_complete));
+		self
+	}
+
+	/// Seconds from start to finish, including delay and repeats. Infinite
+	/// for tweens that repeat forever.
+	pub fn duration(&self) -> f32 {
+		let length = self.track.duration();
+		self.delay
+			+ match self.repeat {
+				Repeat::Once => length,
+				Repeat::Loop(count) => length * count as f32,
+				Repeat::Yoyo(count) => length * count as f32 * 2.0,
+				Repeat::LoopForever | Repeat::YoyoForever => f32::INFINITY,
+			}
+	}
+
+	/// Puts a tween that's played before back to how it started, for when a
+	/// group it's in loops around.
+	fn rewind(&mut self, sk: &Sk) {
+		if self.started {
+			self.track.seek(sk, 0.0);
+		}
+		self.completed = false;
+	}
+
+	/// Jumps to `time` seconds after the tween started.
+	fn seek(&mut self, sk: &Sk, time: f32) {
+		let time = time - self.delay;
+		if time < 0.0 {
+			// Still waiting out the delay, which a looping group can come back to
+			self.rewind(sk);
+			return;
+		}
+		self.started = true;
+		let length = self.track.duration();
+		let total = self.duration() - self.delay;
+		let local = if length <= 0.0 || time >= total {
+			// Finished, so rest on the last frame, backwards for a yoyo
+			match self.repeat {
+				Repeat::Yoyo(_) => 0.0,
+				_ => length,
+			}
+		} else {
+			let pass = (time / length).floor();
+			let within = time - pass * length;
+			match self.repeat {
+				Repeat::Yoyo(_) | Repeat::YoyoForever if pass as u64 % 2 == 1 => length - within,
+				_ => within,
+			}
+		};
+		self.track.seek(sk, local);
+
+		let completed = time >= total;
+		if completed && !self.completed {
+			if let Some(on_complete) = &mut self.on_complete {
+				on_complete();
+			}
+		}
+		self.completed = completed;
+	}
+}
+
+/// Identifies a tween playing on a [`Tweener`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TweenId(u64);
+
+/// Plays tweens! Nothing steps it for you, so call [`Tweener::step`] from
+/// your update every frame. It only moves forward once per frame no matter how
+/// many times it's called, so it's safe to call from wherever is handy.
+pub struct Tweener {
+	tweens: Vec<(TweenId, Tween, f32)>,
+	next_id: u64,
+	last_frame: Option<f64>,
+}
+
+impl Default for Tweener {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Tweener {
+	pub fn new() -> Self {
+		Self {
+			tweens: Vec::new(),
+			next_id: 0,
+			last_frame: None,
+		}
+	}
+
+	/// Starts playing a tween, from the next step on.
+	pub fn add(&mut self, tween: Tween) -> TweenId {
+		self.next_id += 1;
+		let id = TweenId(self.next_id);
+		self.tweens.push((id, tween, 0.0));
+		id
+	}
+
+	/// Stops a tween where it is, returns false if it had already finished.
+	pub fn cancel(&mut self, id: TweenId) -> bool {
+		let count = self.tweens.len();
+		self.tweens.retain(|(tween_id, _, _)| *tween_id != id);
+		count != self.tweens.len()
+	}
+
+	pub fn is_playing(&self, id: TweenId) -> bool {
+		self.tweens.iter().any(|(tween_id, _, _)| *tween_id == id)
+	}
+
+	pub fn len(&self) -> usize {
+		self.tweens.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.tweens.is_empty()
+	}
+
+	pub fn clear(&mut self) {
+		self.tweens.clear();
+	}
+
+	/// Moves every tween forward by this frame's `time_step`.
+	pub fn step(&mut self, sk: &impl StereoKitMultiThread) {
+		let frame = sk.time_total();
+		if self.last_frame == Some(frame) {
+			return;
+		}
+		self.last_frame = Some(frame);
+		self.advance(sk, sk.time_step_f32());
+	}
+
+	/// Moves every tween forward by `seconds`, and drops the ones that finished.
+	pub fn advance(&mut self, sk: &impl StereoKitMultiThread, seconds: f32) {
+		let sk = Sk::from_handle(sk);
+		self.tweens.retain_mut(|(_, tween, time)| {
+			*time += seconds;
+			tween.seek(&sk, *time);
+			*time < tween.duration()
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ease_endpoints() {
+		let eases = [
+			Ease::Linear,
+			Ease::InQuad,
+			Ease::OutQuad,
+			Ease::InOutQuad,
+			Ease::InCubic,
+			Ease::OutCubic,
+			Ease::InOutCubic,
+			Ease::InQuart,
+			Ease::OutQuart,
+			Ease::InOutQuart,
+			Ease::InSine,
+			Ease::OutSine,
+			Ease::InOutSine,
+			Ease::InExpo,
+			Ease::OutExpo,
+			Ease::InOutExpo,
+			Ease::InBack,
+			Ease::OutBack,
+			Ease::InOutBack,
+			Ease::InElastic,
+			Ease::OutElastic,
+			Ease::InOutElastic,
+			Ease::InBounce,
+			Ease::OutBounce,
+			Ease::InOutBounce,
+		];
+		for ease in eases {
+			assert!(ease.apply(0.0).abs() < 1e-5, "{ease:?} at 0");
+			assert!((ease.apply(1.0) - 1.0).abs() < 1e-5, "{ease:?} at 1");
+			assert_eq!(ease.apply(-1.0), ease.apply(0.0), "{ease:?} below 0");
+			assert_eq!(ease.apply(2.0), ease.apply(1.0), "{ease:?} above 1");
+		}
+		assert_eq!(Ease::Linear.apply(0.25), 0.25);
+		assert!((Ease::InOutQuad.apply(0.5) - 0.5).abs() < 1e-5);
+	}
+}